@@ -7,6 +7,8 @@ use core::{
 };
 use zerocopy::FromBytes;
 
+use crate::unit::PageSize;
+
 pub const MAX_VIRTUAL_ADDRESS: usize = usize::MAX;
 
 /// The lower 12 bits of a virtual address correspond to the P1 page frame offset. 
@@ -68,18 +70,72 @@ bitflags! {
         const EXCLUSIVE         = 1 <<  9;
 
         /// Set this bit to forbid execution of the mapped page.
-        /// In other words, if you want the page to be executable, do NOT set this bit. 
+        /// In other words, if you want the page to be executable, do NOT set this bit.
         const NO_EXECUTE        = 1 << 63;
     }
 }
 
+impl EntryFlags {
+    /// Returns `true` if the `PRESENT` bit is set, i.e., this entry is currently mapped.
+    pub const fn is_present(&self) -> bool {
+        self.contains(EntryFlags::PRESENT)
+    }
+
+    /// Returns `true` if the `WRITABLE` bit is set.
+    pub const fn is_writable(&self) -> bool {
+        self.contains(EntryFlags::WRITABLE)
+    }
+
+    /// Returns `true` if the `HUGE_PAGE` bit is set.
+    pub const fn is_huge(&self) -> bool {
+        self.contains(EntryFlags::HUGE_PAGE)
+    }
+
+    /// Returns `true` if the `EXCLUSIVE` bit is set.
+    pub const fn is_exclusive(&self) -> bool {
+        self.contains(EntryFlags::EXCLUSIVE)
+    }
+
+    /// Returns `true` if the `NO_EXECUTE` bit is set.
+    pub const fn is_no_execute(&self) -> bool {
+        self.contains(EntryFlags::NO_EXECUTE)
+    }
+
+    /// Checks that this set of flags is legal to write into a page table entry
+    /// at the given `level`, per the `HUGE_PAGE` rules documented above:
+    /// it must be clear at [`PageTableLevel::P4`] and [`PageTableLevel::P1`],
+    /// and if set at [`PageTableLevel::P3`] or [`PageTableLevel::P2`] it designates
+    /// a 1GiB or 2MiB huge page mapping, respectively.
+    pub const fn validate(&self, level: PageTableLevel) -> Result<(), InvalidEntryFlags> {
+        match level {
+            PageTableLevel::P4 | PageTableLevel::P1 if self.is_huge() => Err(InvalidEntryFlags { level, flags: *self }),
+            _ => Ok(()),
+        }
+    }
+}
 
+/// The four levels of an x86_64 page table hierarchy, from the top-level P4 down to P1,
+/// which holds the final page table entries that point to actual frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageTableLevel {
+    P4,
+    P3,
+    P2,
+    P1,
+}
 
+/// The error returned by [`EntryFlags::validate()`] when a flag combination
+/// is not legal for the given page table level, e.g. `HUGE_PAGE` set at P4 or P1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidEntryFlags {
+    pub level: PageTableLevel,
+    pub flags: EntryFlags,
+}
 /// A physical memory address, which is a `usize` under the hood
 #[derive(
-    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, 
-    Binary, Octal, LowerHex, UpperHex, 
-    BitAnd, BitOr, BitXor, BitAndAssign, BitOrAssign, BitXorAssign, 
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+    Binary, Octal, LowerHex, UpperHex,
+    BitAnd, BitOr, BitXor, BitAndAssign, BitOrAssign, BitXorAssign,
     Add, Sub, AddAssign, SubAssign,
     FromBytes,
 )]
@@ -93,12 +149,12 @@ impl PhysicalAddress {
     /// if their upper bits `(64:48]` are sign-extended from bit 47,
     /// and physical addresses are canonical if their upper bits `(64:52]` are 0.
     pub fn new(addr: usize) -> Option<PhysicalAddress> {
-        if is_canonical_physical_address(addr) { Some(PhysicalAddress(addr)) } else { None }
+        if is_canonical_physical_address::<TargetArchitecture>(addr) { Some(PhysicalAddress(addr)) } else { None }
     }
 
     /// Creates a new `PhysicalAddress` that is guaranteed to be canonical.
     pub const fn new_canonical(addr: usize) -> PhysicalAddress {
-        PhysicalAddress(canonicalize_physical_address(addr))
+        PhysicalAddress(canonicalize_physical_address::<TargetArchitecture>(addr))
     }
 
     /// Creates a new `PhysicalAddress` with a value 0.
@@ -118,6 +174,32 @@ impl PhysicalAddress {
     pub const fn frame_offset(&self) -> usize {
         self.0 & (PAGE_SIZE - 1)
     }
+
+    /// Returns the offset of this address within its enclosing `S`-sized frame,
+    /// e.g. the low 21 bits for a [`crate::unit::Size2MiB`] frame.
+    pub fn offset_within<S: PageSize>(&self) -> usize {
+        self.0 & (S::SIZE - 1)
+    }
+
+    /// Returns `true` if this address falls exactly on an `S`-sized boundary.
+    pub fn is_aligned_to<S: PageSize>(&self) -> bool {
+        self.offset_within::<S>() == 0
+    }
+
+    /// Rounds this address down to the nearest `S`-sized boundary at or below it.
+    pub fn align_down<S: PageSize>(&self) -> PhysicalAddress {
+        PhysicalAddress::new_canonical(self.0 & !(S::SIZE - 1))
+    }
+
+    /// Rounds this address up to the nearest `S`-sized boundary at or above it.
+    pub fn align_up<S: PageSize>(&self) -> PhysicalAddress {
+        let aligned_down = self.align_down::<S>();
+        if aligned_down == *self {
+            aligned_down
+        } else {
+            PhysicalAddress::new_canonical(aligned_down.0.saturating_add(S::SIZE))
+        }
+    }
 }
 impl fmt::Debug for PhysicalAddress {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -181,12 +263,12 @@ impl VirtualAddress {
     /// if their upper bits `(64:48]` are sign-extended from bit 47,
     /// and physical addresses are canonical if their upper bits `(64:52]` are 0.
     pub fn new(addr: usize) -> Option<VirtualAddress> {
-        if is_canonical_virtual_address(addr) { Some(VirtualAddress(addr)) } else { None }
+        if is_canonical_virtual_address::<TargetArchitecture>(addr) { Some(VirtualAddress(addr)) } else { None }
     }
 
     ///Creates a new `VirtualAddress` that is guaranteed to be canonical.
     pub const fn new_canonical(addr: usize) -> VirtualAddress {
-        VirtualAddress(canonicalize_virtual_address(addr))
+        VirtualAddress(canonicalize_virtual_address::<TargetArchitecture>(addr))
     }
 
     ///Creates a new `VirtualAddress` with a value 0.
@@ -206,6 +288,32 @@ impl VirtualAddress {
     pub const fn page_offset(&self) -> usize {
         self.0 & (PAGE_SIZE - 1)
     }
+
+    /// Returns the offset of this address within its enclosing `S`-sized page,
+    /// e.g. the low 21 bits for a [`crate::unit::Size2MiB`] page.
+    pub fn offset_within<S: PageSize>(&self) -> usize {
+        self.0 & (S::SIZE - 1)
+    }
+
+    /// Returns `true` if this address falls exactly on an `S`-sized boundary.
+    pub fn is_aligned_to<S: PageSize>(&self) -> bool {
+        self.offset_within::<S>() == 0
+    }
+
+    /// Rounds this address down to the nearest `S`-sized boundary at or below it.
+    pub fn align_down<S: PageSize>(&self) -> VirtualAddress {
+        VirtualAddress::new_canonical(self.0 & !(S::SIZE - 1))
+    }
+
+    /// Rounds this address up to the nearest `S`-sized boundary at or above it.
+    pub fn align_up<S: PageSize>(&self) -> VirtualAddress {
+        let aligned_down = self.align_down::<S>();
+        if aligned_down == *self {
+            aligned_down
+        } else {
+            VirtualAddress::new_canonical(aligned_down.0.saturating_add(S::SIZE))
+        }
+    }
 }
 impl fmt::Debug for VirtualAddress {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -252,34 +360,174 @@ impl Into<usize> for VirtualAddress {
 }
 
 
+/// Describes how a particular CPU architecture lays out its address space,
+/// so that [`VirtualAddress`] and [`PhysicalAddress`] canonicalization can be
+/// shared across architectures instead of hard-coding x86_64's 48-bit/52-bit widths.
+pub trait AddressArchitecture {
+    /// The highest bit index (0-based) of the non-sign-extended portion of a virtual address.
+    /// On x86_64 this is `47`, since bits `(64:48]` must be sign-extensions of bit 47.
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize;
+    /// Whether bits `(VIRT_ADDR_SIGN_EXTEND_BIT:64]` must be sign-extensions of bit
+    /// `VIRT_ADDR_SIGN_EXTEND_BIT` (the default, e.g. x86_64 and RISC-V Sv39/48/57),
+    /// or must instead be all zero (e.g. an aarch64 TTBR0 lower-half mapping).
+    const VIRT_ADDR_SIGN_EXTENDED: bool = true;
+    /// The number of bits of physical address space this architecture's MMU can address.
+    const PHYS_ADDR_BITS: usize;
+    /// The page/translation-granule shift used by this architecture, i.e. `log2(PAGE_SIZE)`.
+    const PAGE_SHIFT: usize;
+}
+
+/// The x86_64 architecture: 48-bit sign-extended virtual addresses,
+/// a 52-bit physical address space, and 4KiB pages.
+pub struct X86_64;
+impl AddressArchitecture for X86_64 {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const PHYS_ADDR_BITS: usize = 52;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The aarch64 architecture with a 4KiB translation granule: 48-bit sign-extended
+/// virtual addresses and a 48-bit physical address space.
+pub struct Aarch64Granule4KiB;
+impl AddressArchitecture for Aarch64Granule4KiB {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const PHYS_ADDR_BITS: usize = 48;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The aarch64 architecture with a 64KiB translation granule: 48-bit sign-extended
+/// virtual addresses and a 48-bit physical address space.
+pub struct Aarch64Granule64KiB;
+impl AddressArchitecture for Aarch64Granule64KiB {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const PHYS_ADDR_BITS: usize = 48;
+    const PAGE_SHIFT: usize = 16;
+}
+
+/// The aarch64 architecture's TTBR0 (lower-half, userspace) region with a 4KiB
+/// translation granule: unlike [`Aarch64Granule4KiB`]'s TTBR1 region, a TTBR0 address's
+/// upper bits must be all zero rather than sign-extended from bit 47.
+pub struct Aarch64TTBR0Granule4KiB;
+impl AddressArchitecture for Aarch64TTBR0Granule4KiB {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const VIRT_ADDR_SIGN_EXTENDED: bool = false;
+    const PHYS_ADDR_BITS: usize = 48;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The aarch64 architecture's TTBR0 (lower-half, userspace) region with a 64KiB
+/// translation granule; see [`Aarch64TTBR0Granule4KiB`].
+pub struct Aarch64TTBR0Granule64KiB;
+impl AddressArchitecture for Aarch64TTBR0Granule64KiB {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const VIRT_ADDR_SIGN_EXTENDED: bool = false;
+    const PHYS_ADDR_BITS: usize = 48;
+    const PAGE_SHIFT: usize = 16;
+}
+
+/// The RISC-V Sv39 paging mode: 39-bit sign-extended virtual addresses
+/// over a 56-bit physical address space, with 4KiB pages.
+pub struct RiscvSv39;
+impl AddressArchitecture for RiscvSv39 {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 38;
+    const PHYS_ADDR_BITS: usize = 56;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The RISC-V Sv48 paging mode: 48-bit sign-extended virtual addresses
+/// over a 56-bit physical address space, with 4KiB pages.
+pub struct RiscvSv48;
+impl AddressArchitecture for RiscvSv48 {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 47;
+    const PHYS_ADDR_BITS: usize = 56;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The RISC-V Sv57 paging mode: 57-bit sign-extended virtual addresses
+/// over a 56-bit physical address space, with 4KiB pages.
+pub struct RiscvSv57;
+impl AddressArchitecture for RiscvSv57 {
+    const VIRT_ADDR_SIGN_EXTEND_BIT: usize = 56;
+    const PHYS_ADDR_BITS: usize = 56;
+    const PAGE_SHIFT: usize = 12;
+}
+
+/// The architecture this crate canonicalizes addresses for, selected at compile time.
+/// Defaults to [`X86_64`] unless built for `aarch64` or `riscv64`:
+/// * On `aarch64`, the `aarch64_64kib_granule` feature selects between the two supported
+///   translation granules, and `aarch64_ttbr0` selects the TTBR0 (zero-extended) address
+///   range instead of the default TTBR1 (sign-extended) one.
+/// * On `riscv64`, the `riscv_sv48`/`riscv_sv57` features select the Sv48 or Sv57
+///   paging mode instead of the default Sv39.
+#[cfg(not(any(target_arch = "aarch64", target_arch = "riscv64")))]
+pub type TargetArchitecture = X86_64;
+#[cfg(all(target_arch = "aarch64", feature = "aarch64_64kib_granule", not(feature = "aarch64_ttbr0")))]
+pub type TargetArchitecture = Aarch64Granule64KiB;
+#[cfg(all(target_arch = "aarch64", not(feature = "aarch64_64kib_granule"), not(feature = "aarch64_ttbr0")))]
+pub type TargetArchitecture = Aarch64Granule4KiB;
+#[cfg(all(target_arch = "aarch64", feature = "aarch64_64kib_granule", feature = "aarch64_ttbr0"))]
+pub type TargetArchitecture = Aarch64TTBR0Granule64KiB;
+#[cfg(all(target_arch = "aarch64", not(feature = "aarch64_64kib_granule"), feature = "aarch64_ttbr0"))]
+pub type TargetArchitecture = Aarch64TTBR0Granule4KiB;
+#[cfg(all(target_arch = "riscv64", feature = "riscv_sv57"))]
+pub type TargetArchitecture = RiscvSv57;
+#[cfg(all(target_arch = "riscv64", feature = "riscv_sv48", not(feature = "riscv_sv57")))]
+pub type TargetArchitecture = RiscvSv48;
+#[cfg(all(target_arch = "riscv64", not(feature = "riscv_sv48"), not(feature = "riscv_sv57")))]
+pub type TargetArchitecture = RiscvSv39;
+
+/// A type that can be the address of an [`crate::range::AddressRange`]'s unit:
+/// [`PhysicalAddress`] for a [`crate::unit::Frame`], or [`VirtualAddress`] for a [`crate::unit::Page`].
+pub trait Address: Copy + Clone + PartialEq + Eq + PartialOrd + Ord + Add<usize, Output = Self> {
+    /// Returns the underlying `usize` value of this address.
+    fn value(&self) -> usize;
+}
+impl Address for PhysicalAddress {
+    fn value(&self) -> usize {
+        PhysicalAddress::value(self)
+    }
+}
+impl Address for VirtualAddress {
+    fn value(&self) -> usize {
+        VirtualAddress::value(self)
+    }
+}
+
 #[inline]
-fn is_canonical_virtual_address(virt_addr: usize) -> bool {
-    match virt_addr.get_bits(47..64) {
-        0 | 0b1_1111_1111_1111_1111 => true,
+pub(crate) fn is_canonical_virtual_address<A: AddressArchitecture>(virt_addr: usize) -> bool {
+    let shift = 63 - A::VIRT_ADDR_SIGN_EXTEND_BIT;
+    if !A::VIRT_ADDR_SIGN_EXTENDED {
+        return virt_addr.get_bits(A::VIRT_ADDR_SIGN_EXTEND_BIT + 1..64) == 0;
+    }
+    match virt_addr.get_bits(A::VIRT_ADDR_SIGN_EXTEND_BIT..64) {
+        0 => true,
+        prefix if prefix == (1 << (shift + 1)) - 1 => true,
         _ => false,
     }
 }
 
 #[inline]
-const fn canonicalize_virtual_address(virt_addr: usize) -> usize {
-    // match virt_addr.get_bit(47) {
-    //     false => virt_addr.set_bits(48..64, 0),
-    //     true =>  virt_addr.set_bits(48..64, 0xffff),
-    // };
-
-    // The below code is semantically equivalent to the above, but it works in const functions.
-    ((virt_addr << 16) as isize >> 16) as usize
+pub(crate) const fn canonicalize_virtual_address<A: AddressArchitecture>(virt_addr: usize) -> usize {
+    // Sign-extend (or, if `!VIRT_ADDR_SIGN_EXTENDED`, zero-extend) everything above
+    // `VIRT_ADDR_SIGN_EXTEND_BIT` from that bit, which is semantically equivalent to a
+    // `set_bits` on the high half, but works in const functions.
+    let shift = 63 - A::VIRT_ADDR_SIGN_EXTEND_BIT;
+    if A::VIRT_ADDR_SIGN_EXTENDED {
+        ((virt_addr << shift) as isize >> shift) as usize
+    } else {
+        (virt_addr << shift) >> shift
+    }
 }
 
 #[inline]
-fn is_canonical_physical_address(phys_addr: usize) -> bool {
-    match phys_addr.get_bits(52..64) {
+pub(crate) fn is_canonical_physical_address<A: AddressArchitecture>(phys_addr: usize) -> bool {
+    match phys_addr.get_bits(A::PHYS_ADDR_BITS..64) {
         0 => true,
         _ => false,
     }
 }
 
 #[inline]
-const fn canonicalize_physical_address(phys_addr: usize) -> usize {
-    phys_addr & 0x000F_FFFF_FFFF_FFFF
+pub(crate) const fn canonicalize_physical_address<A: AddressArchitecture>(phys_addr: usize) -> usize {
+    phys_addr & ((1 << A::PHYS_ADDR_BITS) - 1)
 }
@@ -0,0 +1,233 @@
+use crate::addr::{EntryFlags, PhysicalAddress};
+use crate::range::{FrameRange, PageRange};
+use crate::unit::{Frame, Page};
+
+/// A type that knows how to give a [`FrameRange`] back to whatever allocator handed it out.
+pub trait FrameDeallocator {
+    /// Returns `frames` to this allocator so it can be reused by future allocations.
+    fn deallocate_frames(&mut self, frames: FrameRange);
+}
+
+/// An owning handle to a [`FrameRange`] that was mapped with the given [`EntryFlags`].
+///
+/// When dropped, `AllocatedFrames` only deallocates its underlying frames if they were
+/// mapped with [`EntryFlags::EXCLUSIVE`] set, i.e., if this handle is known to be the
+/// sole owner of those frames. A non-exclusive (shared or aliased) mapping is left alone,
+/// since some other owner remains responsible for freeing it.
+pub struct AllocatedFrames<D: FrameDeallocator> {
+    frames: FrameRange,
+    flags: EntryFlags,
+    deallocator: Option<D>,
+}
+
+impl<D: FrameDeallocator> AllocatedFrames<D> {
+    /// Creates a new `AllocatedFrames` that will return `frames` to `deallocator` on drop,
+    /// but only if `flags` has [`EntryFlags::EXCLUSIVE`] set.
+    pub fn new(frames: FrameRange, flags: EntryFlags, deallocator: D) -> AllocatedFrames<D> {
+        AllocatedFrames { frames, flags, deallocator: Some(deallocator) }
+    }
+
+    /// Returns the range of [`Frame`](crate::unit::Frame)s owned by this handle.
+    pub fn frames(&self) -> &FrameRange {
+        &self.frames
+    }
+
+    /// Returns the flags these frames were mapped with.
+    pub fn flags(&self) -> EntryFlags {
+        self.flags
+    }
+
+    /// Consumes this handle and returns its underlying [`FrameRange`] without
+    /// deallocating it, e.g. because ownership of the frames is being transferred
+    /// to another `AllocatedFrames` or mapping.
+    pub fn into_frame_range(mut self) -> FrameRange {
+        self.deallocator = None;
+        self.frames.clone()
+    }
+
+    /// Merges `other` into this allocation, as long as the two are adjacent and
+    /// were mapped with the same `flags`.
+    ///
+    /// Only one of the two deallocators is kept, since the merged range is returned
+    /// to it as a single unit on drop; the other is simply discarded without ever
+    /// being invoked, so this never double-frees the frames it covered.
+    /// On failure (non-adjacent ranges or mismatched flags), both `self` and `other`
+    /// are handed back unchanged.
+    pub fn merge(mut self, mut other: AllocatedFrames<D>) -> Result<AllocatedFrames<D>, (AllocatedFrames<D>, AllocatedFrames<D>)> {
+        if self.flags != other.flags {
+            return Err((self, other));
+        }
+        let merged = match self.frames.clone().merge(other.frames.clone()) {
+            Some(merged) => merged,
+            None => return Err((self, other)),
+        };
+        let deallocator = self.deallocator.take().expect("merge: AllocatedFrames had no deallocator");
+        other.deallocator = None;
+        Ok(AllocatedFrames { frames: merged, flags: self.flags, deallocator: Some(deallocator) })
+    }
+}
+
+impl<D: FrameDeallocator + Clone> AllocatedFrames<D> {
+    /// Splits this allocation into two owned halves around `at_frame`: the frames
+    /// below it, and `at_frame` plus everything above it.
+    ///
+    /// Each half gets its own clone of the deallocator, so either can later be
+    /// dropped (or merged, or split again) independently of the other without
+    /// double-freeing the frames it doesn't own.
+    /// Returns `self` unchanged if `at_frame` doesn't fall strictly within this
+    /// allocation, i.e. if one of the two halves would be empty.
+    pub fn split_at(mut self, at_frame: Frame) -> Result<(AllocatedFrames<D>, AllocatedFrames<D>), AllocatedFrames<D>> {
+        let (before, after) = match self.frames.split_at(at_frame) {
+            (Some(before), Some(after)) => (before, after),
+            _ => return Err(self),
+        };
+        let flags = self.flags;
+        let deallocator = self.deallocator.take().expect("split_at: AllocatedFrames had no deallocator");
+        Ok((
+            AllocatedFrames::new(before, flags, deallocator.clone()),
+            AllocatedFrames::new(after, flags, deallocator),
+        ))
+    }
+}
+
+impl<D: FrameDeallocator> Drop for AllocatedFrames<D> {
+    fn drop(&mut self) {
+        if self.flags.is_exclusive() {
+            if let Some(mut deallocator) = self.deallocator.take() {
+                deallocator.deallocate_frames(self.frames.clone());
+            }
+        }
+    }
+}
+
+/// An owning handle to a single, frame-aligned [`PhysicalAddress`] that is known
+/// to be **exclusively** (bijectively, 1-to-1) owned, matching the semantics of
+/// the [`EntryFlags::EXCLUSIVE`] bit. When dropped, it always returns its frame
+/// to `D`, unlike [`AllocatedFrames`] whose exclusivity is conditional on a flag.
+pub struct ExclusiveFrame<D: FrameDeallocator> {
+    addr: PhysicalAddress,
+    deallocator: Option<D>,
+}
+
+impl<D: FrameDeallocator> ExclusiveFrame<D> {
+    /// Creates a new `ExclusiveFrame` that will return its frame to `deallocator`
+    /// on drop. Returns `None` if `addr` isn't aligned to a frame boundary.
+    pub fn new(addr: PhysicalAddress, deallocator: D) -> Option<ExclusiveFrame<D>> {
+        if addr.frame_offset() != 0 {
+            return None;
+        }
+        Some(ExclusiveFrame { addr, deallocator: Some(deallocator) })
+    }
+
+    /// Returns the physical address of the frame this handle owns.
+    pub fn address(&self) -> PhysicalAddress {
+        self.addr
+    }
+
+    /// Consumes this handle and returns its underlying address without
+    /// deallocating the frame, e.g. because ownership is being transferred
+    /// into a page table entry that will manage it from here on.
+    pub fn into_inner(mut self) -> PhysicalAddress {
+        self.deallocator = None;
+        self.addr
+    }
+
+    /// Consumes this handle and intentionally leaks the frame: it is never
+    /// returned to its deallocator, and its memory is never reused.
+    pub fn leak(self) -> PhysicalAddress {
+        self.into_inner()
+    }
+}
+
+impl<D: FrameDeallocator> Drop for ExclusiveFrame<D> {
+    fn drop(&mut self) {
+        if let Some(mut deallocator) = self.deallocator.take() {
+            let frame = Frame::containing_address(self.addr);
+            deallocator.deallocate_frames(FrameRange::new(frame, frame));
+        }
+    }
+}
+
+/// A type that knows how to give a [`PageRange`] back to whatever allocator handed it out.
+pub trait PageDeallocator {
+    /// Returns `pages` to this allocator so it can be reused by future allocations.
+    fn deallocate_pages(&mut self, pages: PageRange);
+}
+
+/// An owning handle to a [`PageRange`] that was mapped with the given [`EntryFlags`].
+///
+/// The virtual-memory twin of [`AllocatedFrames`]: see its docs for the exclusivity
+/// and deallocation semantics, which carry over here unchanged.
+pub struct AllocatedPages<D: PageDeallocator> {
+    pages: PageRange,
+    flags: EntryFlags,
+    deallocator: Option<D>,
+}
+
+impl<D: PageDeallocator> AllocatedPages<D> {
+    /// Creates a new `AllocatedPages` that will return `pages` to `deallocator` on drop,
+    /// but only if `flags` has [`EntryFlags::EXCLUSIVE`] set.
+    pub fn new(pages: PageRange, flags: EntryFlags, deallocator: D) -> AllocatedPages<D> {
+        AllocatedPages { pages, flags, deallocator: Some(deallocator) }
+    }
+
+    /// Returns the range of [`Page`](crate::unit::Page)s owned by this handle.
+    pub fn pages(&self) -> &PageRange {
+        &self.pages
+    }
+
+    /// Returns the flags these pages were mapped with.
+    pub fn flags(&self) -> EntryFlags {
+        self.flags
+    }
+
+    /// Consumes this handle and returns its underlying [`PageRange`] without
+    /// deallocating it, e.g. because ownership of the pages is being transferred
+    /// to another `AllocatedPages` or mapping.
+    pub fn into_page_range(mut self) -> PageRange {
+        self.deallocator = None;
+        self.pages.clone()
+    }
+
+    /// Merges `other` into this allocation. See [`AllocatedFrames::merge`] for the
+    /// adjacency/flags requirements and why this can't double-free.
+    pub fn merge(mut self, mut other: AllocatedPages<D>) -> Result<AllocatedPages<D>, (AllocatedPages<D>, AllocatedPages<D>)> {
+        if self.flags != other.flags {
+            return Err((self, other));
+        }
+        let merged = match self.pages.clone().merge(other.pages.clone()) {
+            Some(merged) => merged,
+            None => return Err((self, other)),
+        };
+        let deallocator = self.deallocator.take().expect("merge: AllocatedPages had no deallocator");
+        other.deallocator = None;
+        Ok(AllocatedPages { pages: merged, flags: self.flags, deallocator: Some(deallocator) })
+    }
+}
+
+impl<D: PageDeallocator + Clone> AllocatedPages<D> {
+    /// Splits this allocation into two owned halves around `at_page`. See
+    /// [`AllocatedFrames::split_at`] for why this needs `D: Clone`.
+    pub fn split_at(mut self, at_page: Page) -> Result<(AllocatedPages<D>, AllocatedPages<D>), AllocatedPages<D>> {
+        let (before, after) = match self.pages.split_at(at_page) {
+            (Some(before), Some(after)) => (before, after),
+            _ => return Err(self),
+        };
+        let flags = self.flags;
+        let deallocator = self.deallocator.take().expect("split_at: AllocatedPages had no deallocator");
+        Ok((
+            AllocatedPages::new(before, flags, deallocator.clone()),
+            AllocatedPages::new(after, flags, deallocator),
+        ))
+    }
+}
+
+impl<D: PageDeallocator> Drop for AllocatedPages<D> {
+    fn drop(&mut self) {
+        if self.flags.is_exclusive() {
+            if let Some(mut deallocator) = self.deallocator.take() {
+                deallocator.deallocate_pages(self.pages.clone());
+            }
+        }
+    }
+}
@@ -9,8 +9,20 @@ use std::{ops::Deref, collections::btree_map::Range};
 
 mod pervasive;
 
+pub mod addr;
+pub mod unit;
+pub mod range_inclusive;
+pub mod range;
+pub mod page_table_entry;
+pub mod allocated_frames;
+pub mod translation;
+
 mod trusted_chunk;
 mod trusted_list;
+mod trusted_range_inclusive;
+
+#[cfg(test)]
+mod test;
 
 // #[derive(Copy,Clone)]
 pub struct Frame {
@@ -0,0 +1,87 @@
+use crate::addr::{EntryFlags, PhysicalAddress, VirtualAddress, PAGE_SHIFT, PAGE_TABLE_ENTRY_FRAME_MASK};
+use crate::unit::{Frame, Page, Size4KiB};
+
+/// A page table entry, which is a 64-bit value that maps a virtual [`Page`]
+/// to a physical [`Frame`] plus a set of [`EntryFlags`], exactly as the hardware lays it out.
+///
+/// This mirrors the `PageTableEntry` decode/encode APIs found in the `x86_64` and `x86` crates.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PageTableEntry(u64);
+
+impl PageTableEntry {
+    /// Creates a new `PageTableEntry` that maps the given `frame` with the given `flags`.
+    pub fn new(frame: Frame, flags: EntryFlags) -> PageTableEntry {
+        PageTableEntry::from_address(frame.start_address(), flags)
+    }
+
+    /// Creates a new `PageTableEntry` that maps the given physical `addr` with the given `flags`.
+    pub fn from_address(addr: PhysicalAddress, flags: EntryFlags) -> PageTableEntry {
+        let frame_bits = (addr.value() as u64) & PAGE_TABLE_ENTRY_FRAME_MASK;
+        PageTableEntry(frame_bits | flags.bits())
+    }
+
+    /// Overwrites this entry in place to map the given physical `addr` with the given `flags`.
+    pub fn set(&mut self, addr: PhysicalAddress, flags: EntryFlags) {
+        *self = PageTableEntry::from_address(addr, flags);
+    }
+
+    /// Returns the physical address pointed to by this entry, or `None` if the entry's
+    /// `PRESENT` flag is not set (i.e., it isn't currently mapped to anything).
+    pub fn frame(&self) -> Option<PhysicalAddress> {
+        if self.flags().is_present() {
+            Some(PhysicalAddress::new_canonical((self.0 & PAGE_TABLE_ENTRY_FRAME_MASK) as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the [`Frame`] pointed to by this entry, or `None` if the entry's
+    /// `PRESENT` flag is not set (i.e., it isn't currently mapped to anything).
+    pub fn pointed_frame(&self) -> Option<Frame> {
+        self.frame().map(Frame::containing_address)
+    }
+
+    /// Returns the [`EntryFlags`] stored in this entry.
+    pub fn flags(&self) -> EntryFlags {
+        EntryFlags::from_bits_truncate(self.0)
+    }
+}
+
+/// Walks a 4-level page table rooted at `root`, translating `virt` to the physical
+/// address it's mapped to, or `None` if any level along the way isn't present.
+///
+/// `access` turns a [`PhysicalAddress`] holding a page table into a pointer this
+/// process can actually dereference (e.g. via a physical-memory mapping window);
+/// this function has no opinion on how physical memory is made accessible.
+/// `HUGE_PAGE` entries at the P3 (1GiB) or P2 (2MiB) level end the walk early,
+/// combining the leaf frame's base address with the remaining low bits of `virt`.
+pub fn translate(
+    root: PhysicalAddress,
+    virt: VirtualAddress,
+    access: impl Fn(PhysicalAddress) -> *const PageTableEntry,
+) -> Option<PhysicalAddress> {
+    let page = Page::<Size4KiB>::containing_address(virt);
+    let indices = [page.p4_index(), page.p3_index(), page.p2_index(), page.p1_index()];
+
+    let mut table_addr = root;
+    for (level, &index) in indices.iter().enumerate() {
+        let entry = unsafe { *access(table_addr).add(index) };
+        let entry_addr = entry.frame()?;
+
+        let is_last_level = level == indices.len() - 1;
+        if is_last_level || entry.flags().is_huge() {
+            let huge_page_shift = match level {
+                1 => 30, // P3 huge page: 1GiB
+                2 => 21, // P2 huge page: 2MiB
+                _ => PAGE_SHIFT, // P1 leaf: standard 4KiB page
+            };
+            let offset_mask = (1usize << huge_page_shift) - 1;
+            return Some(PhysicalAddress::new_canonical(entry_addr.value() | (virt.value() & offset_mask)));
+        }
+
+        table_addr = entry_addr;
+    }
+
+    None
+}
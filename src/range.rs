@@ -1,68 +1,52 @@
-// use bit_field::BitField;
 use core::{
     cmp::{min, max},
     fmt,
-    iter::Step,
-    ops::{Add, AddAssign, Deref, DerefMut, Sub, SubAssign},
+    ops::{Deref, DerefMut},
 };
-// use zerocopy::FromBytes;
+
+use arrayvec::ArrayVec;
 
 use crate::{addr::*, unit::*, range_inclusive::*};
 
-/// A range of [`Frame`]s that are contiguous in physical memory.
+/// A range of [`Unit`]s (i.e. [`Frame`]s or [`Page`]s) that are contiguous in memory.
+///
+/// This is generic over the kind of unit so that [`FrameRange`] and [`PageRange`]
+/// can share one implementation of range arithmetic instead of duplicating it.
 #[derive(Clone, PartialEq, Eq)]
-pub struct FrameRange(RangeInclusive<Frame>);
-
-impl FrameRange {
-    /// Creates a new range of [`Frame`]s that spans from `start` to `end`, both inclusive bounds.
-    pub const fn new(start: Frame, end: Frame) -> FrameRange {
-        FrameRange(RangeInclusive::new(start, end))
-    }
+pub struct AddressRange<U: Unit>(RangeInclusive<U>);
 
-    /// Creates a `FrameRange` that will always yield `None` when iterated.
-    pub const fn empty() -> FrameRange {
-        FrameRange::new(Frame { number: 1 }, Frame { number: 0 })
-    }
-
-    /// A convenience method for creating a new `FrameRange` that spans
-    /// all [`Frame`]s from the given [`PhysicalAddress`] to an end bound based on the given size.
-    pub fn from_phys_addr(starting_addr: PhysicalAddress, size_in_bytes: usize) -> FrameRange {
-        assert!(size_in_bytes > 0);
-        let start = Frame::containing_address(starting_addr);
-        // The end bound is inclusive, hence the -1. Parentheses are needed to avoid overflow.
-        let end = Frame::containing_address(starting_addr + (size_in_bytes - 1));
-        FrameRange::new(start, end)
+impl<U: Unit> AddressRange<U> {
+    /// Creates a new range of `U`s that spans from `start` to `end`, both inclusive bounds.
+    pub const fn new(start: U, end: U) -> AddressRange<U> {
+        AddressRange(RangeInclusive::new(start, end))
     }
 
-    /// Returns the [`PhysicalAddress`] of the starting [`Frame`] in this `FrameRange`.
-    pub const fn start_address(&self) -> PhysicalAddress {
+    /// Returns the address of the starting unit in this range.
+    pub fn start_address(&self) -> U::Address {
         self.0.start().start_address()
     }
 
-    /// Returns the number of [`Frame`]s covered by this iterator.
-    /// Use this instead of [`Iterator::count()`] method.
-    /// This is instant, because it doesn't need to iterate over each entry, unlike normal iterators.
-    pub const fn size_in_frames(&self) -> usize {
+    /// Returns the number of units covered by this range.
+    /// Use this instead of [`Iterator::count()`], since it doesn't need to iterate.
+    pub fn size_in_units(&self) -> usize {
         // add 1 because it's an inclusive range
-        (self.0.end().number + 1).saturating_sub(self.0.start().number)
+        (self.0.end().number() + 1).saturating_sub(self.0.start().number())
     }
 
     /// Returns the size of this range in number of bytes.
-    pub const fn size_in_bytes(&self) -> usize {
-        self.size_in_frames() * PAGE_SIZE
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_units() * U::Size::SIZE
     }
 
-    /// Returns `true` if this `FrameRange` contains the given [`PhysicalAddress`].
-    pub fn contains_address(&self, addr: PhysicalAddress) -> bool {
-        self.0.contains(&Frame::containing_address(addr))
+    /// Returns `true` if this range contains the given address.
+    pub fn contains_address(&self, addr: U::Address) -> bool {
+        self.0.contains(&U::containing_address(addr))
     }
 
-    /// Returns the offset of the given [`PhysicalAddress`] within this `FrameRange`,
+    /// Returns the offset of the given address within this range,
     /// i.e., `addr - self.start_address()`.
-    /// If the given `addr` is not covered by this range of [`Frame`]s, this returns `None`.
-    /// # Examples
-    /// If the range covers addresses `0x2000` to `0x4000`, then `offset_of_address(0x3500)` would return `Some(0x1500)`.
-    pub fn offset_of_address(&self, addr: PhysicalAddress) -> Option<usize> {
+    /// If the given `addr` is not covered by this range, this returns `None`.
+    pub fn offset_of_address(&self, addr: U::Address) -> Option<usize> {
         if self.contains_address(addr) {
             Some(addr.value() - self.start_address().value())
         } else {
@@ -70,190 +54,267 @@ impl FrameRange {
         }
     }
 
-    /// Returns the [`PhysicalAddress`] at the given `offset` into this `FrameRange` within this `FrameRange`,
-    /// i.e., `addr - self.start_address()`.\n\n \
-    /// If the given `offset` is not within this range of [`Frame`]s, this returns `None`.\n\n \
-    /// # Examples\n \
-    /// If the range covers addresses `0x2000` to `0x4000`, then `address_at_offset(0x1500)` would return `Some(0x3500)`.
-    pub fn address_at_offset(&self, offset: usize) -> Option<PhysicalAddress> {
+    /// Returns the address at the given `offset` into this range,
+    /// i.e., `self.start_address() + offset`.
+    /// If the given `offset` is not within this range, this returns `None`.
+    pub fn address_at_offset(&self, offset: usize) -> Option<U::Address> {
         if offset <= self.size_in_bytes() {
             Some(self.start_address() + offset)
-        }
-        else {
+        } else {
             None
         }
     }
 
-    /// "Returns a new separate `FrameRange` that is extended to include the given [`Frame`].
-    pub fn to_extended(&self, to_include: Frame) -> FrameRange {
-        // if the current range was empty, return a new range containing only the given page/frame
+    /// Returns a new separate range that is extended to include the given unit.
+    pub fn to_extended(&self, to_include: U) -> AddressRange<U> {
+        // if the current range was empty, return a new range containing only the given unit
         if self.is_empty() {
-            return FrameRange::new(to_include.clone(), to_include);
+            return AddressRange::new(to_include, to_include);
         }
-        let start = core::cmp::min(self.0.start(), &to_include);
-        let end = core::cmp::max(self.0.end(), &to_include);
-        FrameRange::new(start.clone(), end.clone())
+        let start = min(*self.0.start(), to_include);
+        let end = max(*self.0.end(), to_include);
+        AddressRange::new(start, end)
     }
 
-    /// "Returns an inclusive `FrameRange` representing the [`Frame`]s that overlap \
-    /// across this `FrameRange` and the given other `FrameRange`.\n\n \
+    /// Returns an inclusive range representing the units that overlap
+    /// across this range and the given `other` range.
     /// If there is no overlap between the two ranges, `None` is returned.
-    pub fn overlap(&self, other: &FrameRange) -> Option<FrameRange> {
+    pub fn overlap(&self, other: &AddressRange<U>) -> Option<AddressRange<U>> {
+        self.intersection(other)
+    }
+
+    /// Returns `true` if this range and `other` share at least one unit.
+    pub fn overlaps(&self, other: &AddressRange<U>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the intersection of this range and `other`,
+    /// i.e., `[max(self.start, other.start), min(self.end, other.end)]`.
+    /// Returns `None` if the two ranges do not overlap.
+    pub fn intersection(&self, other: &AddressRange<U>) -> Option<AddressRange<U>> {
         let starts = max(*self.start(), *other.start());
         let ends   = min(*self.end(),   *other.end());
         if starts <= ends {
-            Some(FrameRange::new(starts, ends))
+            Some(AddressRange::new(starts, ends))
+        } else {
+            None
+        }
+    }
+
+    /// Splits this range into two sub-ranges around `mid`:
+    /// the range below `mid` (exclusive) and the range from `mid` (inclusive) upward.
+    /// Either side is `None` if it would be empty.
+    pub fn split_at(&self, mid: U) -> (Option<AddressRange<U>>, Option<AddressRange<U>>) {
+        if !self.contains(&mid) {
+            return if mid <= *self.start() {
+                (None, Some(self.clone()))
+            } else {
+                (Some(self.clone()), None)
+            };
+        }
+
+        let first = if mid > *self.start() {
+            Some(AddressRange::new(*self.start(), mid - 1))
         } else {
             None
+        };
+        let second = Some(AddressRange::new(mid, *self.end()));
+        (first, second)
+    }
+
+    /// Returns `true` if this range and `other` are contiguous,
+    /// i.e., they overlap or one starts exactly where the other ends.
+    pub fn adjacent(&self, other: &AddressRange<U>) -> bool {
+        if self.overlaps(other) {
+            return true;
         }
+        (self.end().number() + 1 == other.start().number())
+            || (other.end().number() + 1 == self.start().number())
+    }
+
+    /// Merges this range with `other` into a single contiguous range,
+    /// as long as the two ranges touch or overlap so the result has no gap.
+    /// Returns `None` if the ranges are neither overlapping nor adjacent.
+    pub fn merge(self, other: AddressRange<U>) -> Option<AddressRange<U>> {
+        if !self.adjacent(&other) {
+            return None;
+        }
+        let start = min(*self.start(), *other.start());
+        let end   = max(*self.end(),   *other.end());
+        Some(AddressRange::new(start, end))
+    }
+
+    /// Carves `allocated` out of this range, returning the leftover sub-ranges
+    /// that remain before and after it, in that order.
+    /// Returns `None` if `allocated` is not fully contained within this range.
+    pub fn carve(&self, allocated: &AddressRange<U>) -> Option<(Option<AddressRange<U>>, Option<AddressRange<U>>)> {
+        if !self.contains(allocated.start()) || !self.contains(allocated.end()) {
+            return None;
+        }
+
+        let before = if *allocated.start() > *self.start() {
+            Some(AddressRange::new(*self.start(), *allocated.start() - 1))
+        } else {
+            None
+        };
+        let after = if *allocated.end() < *self.end() {
+            Some(AddressRange::new(*allocated.end() + 1, *self.end()))
+        } else {
+            None
+        };
+        Some((before, after))
+    }
+
+    /// Subtracts `other` from this range, returning the leftover sub-ranges
+    /// that still belong to `self`: empty if `other` fully covers `self`,
+    /// one range if `other` overlaps only one edge of `self`, or two ranges
+    /// if `other` is a strict sub-range carved out of the middle of `self`.
+    /// Returns `self` unchanged (as the sole element) if the two ranges don't overlap at all.
+    ///
+    /// This is the partial-overlap counterpart to [`carve()`](Self::carve),
+    /// which instead requires `other` to be fully contained within `self`.
+    pub fn subtract(&self, other: &AddressRange<U>) -> ArrayVec<[AddressRange<U>; 2]> {
+        let mut remainders = ArrayVec::new();
+        let overlap = match self.overlap(other) {
+            Some(overlap) => overlap,
+            None => {
+                remainders.push(self.clone());
+                return remainders;
+            }
+        };
+        if let Some((before, after)) = self.carve(&overlap) {
+            if let Some(before) = before {
+                remainders.push(before);
+            }
+            if let Some(after) = after {
+                remainders.push(after);
+            }
+        }
+        remainders
     }
 }
-impl fmt::Debug for FrameRange {
+impl<U: Unit> fmt::Debug for AddressRange<U> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.0)
     }
 }
-impl Deref for FrameRange {
-    type Target = RangeInclusive<Frame>;
-    fn deref(&self) -> &RangeInclusive<Frame> {
+impl<U: Unit> Deref for AddressRange<U> {
+    type Target = RangeInclusive<U>;
+    fn deref(&self) -> &RangeInclusive<U> {
         &self.0
     }
 }
-impl DerefMut for FrameRange {
-    fn deref_mut(&mut self) -> &mut RangeInclusive<Frame> {
+impl<U: Unit> DerefMut for AddressRange<U> {
+    fn deref_mut(&mut self) -> &mut RangeInclusive<U> {
         &mut self.0
     }
 }
-impl<'a> IntoIterator for &'a FrameRange {
-    type Item = Frame;
-    type IntoIter = RangeInclusiveIterator<Frame>;
+impl<'a, U: Unit> IntoIterator for &'a AddressRange<U> {
+    type Item = U;
+    type IntoIter = RangeInclusiveIterator<U>;
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
     }
 }
 
+/// A range of [`Frame`]s of size `S` (default [`Size4KiB`]) that are contiguous in physical memory.
+pub type FrameRange<S = Size4KiB> = AddressRange<Frame<S>>;
 
-
-/// A range of [`Page`]s that are contiguous in virtual memory.
-#[derive(Clone, PartialEq, Eq)]
-pub struct PageRange(RangeInclusive<Page>);
-
-impl PageRange {
-    /// Creates a new range of [`Page`]s that spans from `start` to `end`, both inclusive bounds.
-    pub const fn new(start: Page, end: Page) -> PageRange {
-        PageRange(RangeInclusive::new(start, end))
-    }
-
-    /// Creates a `PageRange` that will always yield `None` when iterated.
-    pub const fn empty() -> PageRange {
-        PageRange::new(Page { number: 1 }, Page { number: 0 })
+impl<S: PageSize> FrameRange<S> {
+    /// Creates a `FrameRange` that will always yield `None` when iterated.
+    pub const fn empty() -> FrameRange<S> {
+        FrameRange::new(
+            Frame { number: 1, _size: core::marker::PhantomData },
+            Frame { number: 0, _size: core::marker::PhantomData },
+        )
     }
 
-    /// A convenience method for creating a new `PageRange` that spans
-    /// all [`Page`]s from the given [`VirtualAddress`] to an end bound based on the given size.
-    pub fn from_virt_addr(starting_addr: VirtualAddress, size_in_bytes: usize) -> PageRange {
+    /// A convenience method for creating a new `FrameRange` that spans
+    /// all [`Frame`]s from the given [`PhysicalAddress`] to an end bound based on the given size.
+    pub fn from_phys_addr(starting_addr: PhysicalAddress, size_in_bytes: usize) -> FrameRange<S> {
         assert!(size_in_bytes > 0);
-        let start = Page::containing_address(starting_addr);
+        let start = Frame::containing_address(starting_addr);
         // The end bound is inclusive, hence the -1. Parentheses are needed to avoid overflow.
-        let end = Page::containing_address(starting_addr + (size_in_bytes - 1));
-        PageRange::new(start, end)
+        let end = Frame::containing_address(starting_addr + (size_in_bytes - 1));
+        FrameRange::new(start, end)
     }
 
-    /// Returns the [`VirtualAddress`] of the starting [`Page`] in this `PageRange`.
-    pub const fn start_address(&self) -> VirtualAddress {
-        self.0.start().start_address()
-    }
+    /// Finds and splits out a sub-range of `num_frames` frames, aligned to a multiple
+    /// of `alignment_frames`, from within this range.
+    ///
+    /// On success, returns the aligned, allocated `FrameRange` together with the
+    /// leftover ranges before and after it (either is `None` if there's no leftover
+    /// on that side). Returns `None` if `num_frames` or `alignment_frames` is zero,
+    /// if rounding `self.start()` up to the next multiple of `alignment_frames`
+    /// would overflow `usize::MAX`, or if the aligned allocation doesn't fit within
+    /// this range.
+    pub fn allocate(
+        &self,
+        num_frames: usize,
+        alignment_frames: usize,
+    ) -> Option<(FrameRange<S>, (Option<FrameRange<S>>, Option<FrameRange<S>>))> {
+        if num_frames == 0 || alignment_frames == 0 {
+            return None;
+        }
 
-    /// Returns the number of [`Page`]s covered by this iterator.\n\n \
-    /// Use this instead of [`Iterator::count()`] method. \
-    /// This is instant, because it doesn't need to iterate over each entry, unlike normal iterators.
-    pub const fn size_in_pages(&self) -> usize {
-        // add 1 because it's an inclusive range
-        (self.0.end().number + 1).saturating_sub(self.0.start().number)
-    }
+        let start_number = self.start().number();
+        let aligned_start_number = start_number.checked_add(alignment_frames - 1)?
+            / alignment_frames
+            * alignment_frames;
+        let aligned_end_number = aligned_start_number.checked_add(num_frames - 1)?;
+        if aligned_end_number > self.end().number() {
+            return None;
+        }
 
-    /// Returns the size of this range in number of bytes.
-    pub const fn size_in_bytes(&self) -> usize {
-        self.size_in_pages() * PAGE_SIZE
+        let allocated = FrameRange::new(
+            Frame { number: aligned_start_number, _size: core::marker::PhantomData },
+            Frame { number: aligned_end_number, _size: core::marker::PhantomData },
+        );
+        let remainders = self.carve(&allocated)?;
+        Some((allocated, remainders))
     }
+}
 
-    /// Returns `true` if this `PageRange` contains the given [`VirtualAddress`].
-    pub fn contains_address(&self, addr: VirtualAddress) -> bool {
-        self.0.contains(&Page::containing_address(addr))
+impl FrameRange<Size4KiB> {
+    /// Returns `true` if both the start and end of this range fall on `T`-sized granule boundaries.
+    pub fn is_aligned_to<T: PageSize>(&self) -> bool {
+        self.start_address().value() % T::SIZE == 0
+            && (self.start_address().value() + self.size_in_bytes()) % T::SIZE == 0
     }
 
-    /// Returns the offset of the given [`VirtualAddress`] within this `PageRange`, \
-    /// i.e., `addr - self.start_address()`.\n\n \
-    /// If the given `addr` is not covered by this range of [`Page`]s, this returns `None`.\n\n \
-    /// # Examples\n \
-    /// If the range covers addresses `0x2000` to `0x4000`, then `offset_of_address(0x3500)` would return `Some(0x1500)`.
-    pub fn offset_of_address(&self, addr: VirtualAddress) -> Option<usize> {
-        if self.contains_address(addr) {
-            Some(addr.value() - self.start_address().value())
-        } else {
-            None
+    /// Promotes this range of standard 4KiB frames into the equivalent range of `T`-sized
+    /// huge frames, as long as this range is aligned to `T`'s granule.
+    /// Returns `None` if the range isn't aligned, since it couldn't be mapped as huge frames.
+    pub fn try_into_huge<T: PageSize>(&self) -> Option<FrameRange<T>>
+    where
+        Frame<Size4KiB>: IntoHugeFrame<T>,
+    {
+        if !self.is_aligned_to::<T>() {
+            return None;
         }
+        Some(AddressRange::new(self.start().into_huge_frame(), self.end().into_huge_frame()))
     }
+}
 
-    /// Returns the [`VirtualAddress`] at the given `offset` into this `PageRange`within this `PageRange`, \
-    /// i.e., `addr - self.start_address()`.\n\n \
-    /// If the given `offset` is not within this range of [`Page`]s, this returns `None`.\n\n \
-    /// # Examples\n \
-    /// If the range covers addresses `0x2000` to `0x4000`, then `address_at_offset(0x1500)` would return `Some(0x3500)`.
-    pub fn address_at_offset(&self, offset: usize) -> Option<VirtualAddress> {
-        if offset <= self.size_in_bytes() {
-            Some(self.start_address() + offset)
-        }
-        else {
-            None
-        }
-    }
+/// A range of [`Page`]s of size `S` (default [`Size4KiB`]) that are contiguous in virtual memory.
+pub type PageRange<S = Size4KiB> = AddressRange<Page<S>>;
 
-    /// Returns a new separate `PageRange` that is extended to include the given [`Page`].
-    pub fn to_extended(&self, to_include: Page) -> PageRange {
-        // if the current range was empty, return a new range containing only the given page/frame
-        if self.is_empty() {
-            return PageRange::new(to_include.clone(), to_include);
-        }
-        let start = core::cmp::min(self.0.start(), &to_include);
-        let end = core::cmp::max(self.0.end(), &to_include);
-        PageRange::new(start.clone(), end.clone())
+impl<S: PageSize> PageRange<S> {
+    /// Creates a `PageRange` that will always yield `None` when iterated.
+    pub const fn empty() -> PageRange<S> {
+        PageRange::new(
+            Page { number: 1, _size: core::marker::PhantomData },
+            Page { number: 0, _size: core::marker::PhantomData },
+        )
     }
 
-    /// Returns an inclusive `PageRange` representing the [`Page`]s that overlap \
-    /// across this `PageRange` and the given other `PageRange`.\n\n \
-    /// If there is no overlap between the two ranges, `None` is returned.
-    pub fn overlap(&self, other: &PageRange) -> Option<PageRange> {
-        let starts = max(*self.start(), *other.start());
-        let ends   = min(*self.end(),   *other.end());
-        if starts <= ends {
-            Some(PageRange::new(starts, ends))
-        } else {
-            None
-        }
-    }
-}
-impl fmt::Debug for PageRange {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.0)
-    }
-}
-impl Deref for PageRange {
-    type Target = RangeInclusive<Page>;
-    fn deref(&self) -> &RangeInclusive<Page> {
-        &self.0
-    }
-}
-impl DerefMut for PageRange {
-    fn deref_mut(&mut self) -> &mut RangeInclusive<Page> {
-        &mut self.0
-    }
-}
-impl <'a>IntoIterator for &'a PageRange {
-    type Item = Page;
-    type IntoIter = RangeInclusiveIterator<Page>;
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    /// A convenience method for creating a new `PageRange` that spans
+    /// all [`Page`]s from the given [`VirtualAddress`] to an end bound based on the given size.
+    pub fn from_virt_addr(starting_addr: VirtualAddress, size_in_bytes: usize) -> PageRange<S> {
+        assert!(size_in_bytes > 0);
+        let start = Page::containing_address(starting_addr);
+        // The end bound is inclusive, hence the -1. Parentheses are needed to avoid overflow.
+        let end = Page::containing_address(starting_addr + (size_in_bytes - 1));
+        PageRange::new(start, end)
     }
 }
-
@@ -1,4 +1,5 @@
 mod range_inclusive {
+    use core::marker::PhantomData;
     use crate::{
         addr::*,
         unit::*,
@@ -8,28 +9,28 @@ mod range_inclusive {
 
     #[test]
     fn greater_end() {
-        let range = RangeInclusive::new(Frame{ number: 0} , Frame{ number: 1 });
+        let range = RangeInclusive::new(Frame{ number: 0, _size: PhantomData }, Frame{ number: 1, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", range);
 
-        let range = RangeInclusive::new(Frame{ number: 10} , Frame{ number: 17 });
+        let range = RangeInclusive::new(Frame{ number: 10, _size: PhantomData }, Frame{ number: 17, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", range);
-        
-        let range = RangeInclusive::new(Frame{ number: 3} , Frame{ number: 22 });
+
+        let range = RangeInclusive::new(Frame{ number: 3, _size: PhantomData }, Frame{ number: 22, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", range);
-        
-        let range = RangeInclusive::new(Frame{ number: 597} , Frame{ number: 782 });
+
+        let range = RangeInclusive::new(Frame{ number: 597, _size: PhantomData }, Frame{ number: 782, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
@@ -39,14 +40,14 @@ mod range_inclusive {
 
     #[test]
     fn equal_start_end() {
-        let range = RangeInclusive::new(Frame{ number: 0} , Frame{ number: 0});
+        let range = RangeInclusive::new(Frame{ number: 0, _size: PhantomData }, Frame{ number: 0, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", range);
-        
-        let range = RangeInclusive::new(Frame{ number: 597} , Frame{ number: 597});
+
+        let range = RangeInclusive::new(Frame{ number: 597, _size: PhantomData }, Frame{ number: 597, _size: PhantomData });
         assert!(!range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
@@ -56,14 +57,14 @@ mod range_inclusive {
 
     #[test]
     fn greater_start() {
-        let range = RangeInclusive::new(Frame{ number: 782} , Frame{ number: 597 });
+        let range = RangeInclusive::new(Frame{ number: 782, _size: PhantomData }, Frame{ number: 597, _size: PhantomData });
         assert!(range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", range);
-        
-        let range = RangeInclusive::new(Frame{ number: 1} , Frame{ number: 0 });
+
+        let range = RangeInclusive::new(Frame{ number: 1, _size: PhantomData }, Frame{ number: 0, _size: PhantomData });
         assert!(range.is_empty());
         for r in range.iter() {
             println!("{:?}", r);
@@ -73,6 +74,7 @@ mod range_inclusive {
 }
 
 mod range {
+    use core::marker::PhantomData;
     use crate::{
         addr::*,
         unit::*,
@@ -80,27 +82,30 @@ mod range {
         range::*
     };
 
+    fn frame(number: usize) -> Frame {
+        Frame { number, _size: PhantomData }
+    }
+
     #[test]
     fn test_contains() {
-        let fr = FrameRange::new(Frame{ number: 1 }, Frame{ number: 5 });
-        assert!(fr.contains(&Frame{ number: 3 }));
-        assert!(fr.contains(&Frame{ number: 1 }));
-        assert!(fr.contains(&Frame{ number: 5 }));
-        assert!(!fr.contains(&Frame{ number: 0 }));
-        assert!(!fr.contains(&Frame{ number: 6 }));
-
+        let fr = FrameRange::new(frame(1), frame(5));
+        assert!(fr.contains(&frame(3)));
+        assert!(fr.contains(&frame(1)));
+        assert!(fr.contains(&frame(5)));
+        assert!(!fr.contains(&frame(0)));
+        assert!(!fr.contains(&frame(6)));
     }
 
     #[test]
     fn test_iterator() {
-        let fr = FrameRange::new(Frame{ number: 1 }, Frame{ number: 5 });
+        let fr = FrameRange::new(frame(1), frame(5));
         assert!(!fr.is_empty());
         for r in fr.iter() {
             println!("{:?}", r);
         }
         println!("original range: {:?} \n", fr);
 
-        let fr = FrameRange::new(Frame{ number: 1 }, Frame{ number: 0 });
+        let fr = FrameRange::new(frame(1), frame(0));
         assert!(fr.is_empty());
         for r in fr.iter() {
             println!("{:?}", r);
@@ -108,5 +113,444 @@ mod range {
         println!("original range: {:?} \n", fr);
     }
 
+    #[test]
+    fn test_overlap_and_intersection() {
+        let a = FrameRange::new(frame(0), frame(9));
+        let b = FrameRange::new(frame(5), frame(14));
+        let c = FrameRange::new(frame(20), frame(29));
+
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+        assert!(!a.overlaps(&c));
+
+        assert_eq!(a.intersection(&b), Some(FrameRange::new(frame(5), frame(9))));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.overlap(&b), a.intersection(&b));
+    }
+
+    #[test]
+    fn test_split_at() {
+        let a = FrameRange::new(frame(0), frame(9));
+
+        let (before, after) = a.split_at(frame(5));
+        assert_eq!(before, Some(FrameRange::new(frame(0), frame(4))));
+        assert_eq!(after, Some(FrameRange::new(frame(5), frame(9))));
+
+        let (before, after) = a.split_at(frame(0));
+        assert_eq!(before, None);
+        assert_eq!(after, Some(FrameRange::new(frame(0), frame(9))));
+
+        let (before, after) = a.split_at(frame(20));
+        assert_eq!(before, Some(a.clone()));
+        assert_eq!(after, None);
+    }
+
+    #[test]
+    fn test_adjacent_and_merge() {
+        let a = FrameRange::new(frame(0), frame(9));
+        let b = FrameRange::new(frame(10), frame(19));
+        let c = FrameRange::new(frame(20), frame(29));
+
+        assert!(a.adjacent(&b));
+        assert!(!a.adjacent(&c));
+        assert_eq!(a.clone().merge(b.clone()), Some(FrameRange::new(frame(0), frame(19))));
+        assert_eq!(a.clone().merge(c.clone()), None);
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = FrameRange::new(frame(0), frame(9));
+
+        // Fully contained `other`: leaves both a before and an after remainder.
+        let middle = FrameRange::new(frame(3), frame(5));
+        let remainders = a.subtract(&middle);
+        assert_eq!(remainders.as_slice(), &[FrameRange::new(frame(0), frame(2)), FrameRange::new(frame(6), frame(9))][..]);
+
+        // `other` covers the whole range: no remainder.
+        assert!(a.subtract(&a).is_empty());
+
+        // `other` doesn't overlap at all: `self` comes back unchanged.
+        let disjoint = FrameRange::new(frame(20), frame(29));
+        let remainders = a.subtract(&disjoint);
+        assert_eq!(remainders.as_slice(), &[a.clone()][..]);
+    }
+
+    #[test]
+    fn test_allocate() {
+        let free = FrameRange::new(frame(0), frame(99));
+
+        // The range start is already aligned: the allocation begins there,
+        // leaving a remainder only after it.
+        let (allocated, (before, after)) = free.allocate(4, 4).unwrap();
+        assert_eq!(allocated, FrameRange::new(frame(0), frame(3)));
+        assert_eq!(before, None);
+        assert_eq!(after, Some(FrameRange::new(frame(4), frame(99))));
+
+        // A larger alignment that frame 0 also meets gives the same allocation.
+        let (allocated, (before, after)) = free.allocate(4, 8).unwrap();
+        assert_eq!(allocated, FrameRange::new(frame(0), frame(3)));
+        assert_eq!(before, None);
+        assert_eq!(after, Some(FrameRange::new(frame(4), frame(99))));
+
+        // An alignment that isn't already met rounds the start up,
+        // leaving a remainder before the allocation.
+        let small = FrameRange::new(frame(1), frame(3));
+        let (allocated, (before, after)) = small.allocate(2, 2).unwrap();
+        assert_eq!(allocated, FrameRange::new(frame(2), frame(3)));
+        assert_eq!(before, Some(FrameRange::new(frame(1), frame(1))));
+        assert_eq!(after, None);
+
+        // Doesn't fit: not enough frames left after rounding up to the alignment.
+        assert!(small.allocate(3, 2).is_none());
+
+        // Zero frames or zero alignment are both invalid requests.
+        assert!(free.allocate(0, 4).is_none());
+        assert!(free.allocate(4, 0).is_none());
+    }
+}
+
+mod translation {
+    use crate::{addr::*, translation::*};
+
+    #[test]
+    fn zero_offset_round_trips_canonical_high_half_address() {
+        // A canonical higher-half address, e.g. the start of the kernel's direct map
+        // on x86_64: its MSB is set, so casting it through `isize` makes it negative
+        // even though it represents a large positive `usize` value.
+        let high_half = VirtualAddress::new_canonical(0xFFFF_8000_0000_0000);
+        let translation = LinearTranslation::new(0).unwrap();
+
+        assert_eq!(
+            translation.virt_to_phys(high_half),
+            Some(PhysicalAddress::new_canonical(0xFFFF_8000_0000_0000)),
+        );
+        assert_eq!(
+            translation.phys_to_virt(PhysicalAddress::new_canonical(0xFFFF_8000_0000_0000)),
+            Some(high_half),
+        );
+    }
+
+    #[test]
+    fn positive_offset_round_trips() {
+        let translation = LinearTranslation::new(0x1000).unwrap();
+        let virt = VirtualAddress::new_canonical(0x2000);
+        let phys = translation.virt_to_phys(virt).unwrap();
+        assert_eq!(phys, PhysicalAddress::new_canonical(0x3000));
+        assert_eq!(translation.phys_to_virt(phys), Some(virt));
+    }
+
+    #[test]
+    fn negative_offset_round_trips() {
+        let translation = LinearTranslation::new(-0x1000).unwrap();
+        let virt = VirtualAddress::new_canonical(0x3000);
+        let phys = translation.virt_to_phys(virt).unwrap();
+        assert_eq!(phys, PhysicalAddress::new_canonical(0x2000));
+        assert_eq!(translation.phys_to_virt(phys), Some(virt));
+    }
+
+    #[test]
+    fn misaligned_offset_is_rejected() {
+        assert!(LinearTranslation::new(0x1).is_none());
+    }
+}
+
+mod entry_flags {
+    use crate::addr::*;
+
+    #[test]
+    fn validate_rejects_huge_page_only_at_p4_and_p1() {
+        let huge = EntryFlags::PRESENT | EntryFlags::HUGE_PAGE;
+
+        for level in [PageTableLevel::P4, PageTableLevel::P1] {
+            assert_eq!(huge.validate(level), Err(InvalidEntryFlags { level, flags: huge }));
+        }
+        for level in [PageTableLevel::P3, PageTableLevel::P2] {
+            assert_eq!(huge.validate(level), Ok(()));
+        }
+    }
+
+    #[test]
+    fn validate_always_accepts_flags_without_huge_page() {
+        let normal = EntryFlags::PRESENT | EntryFlags::WRITABLE;
+        for level in [PageTableLevel::P4, PageTableLevel::P3, PageTableLevel::P2, PageTableLevel::P1] {
+            assert_eq!(normal.validate(level), Ok(()));
+        }
+    }
+}
+
+mod address_canonicalization {
+    use crate::addr::*;
+
+    /// Asserts that `A`'s virtual addresses are sign-extended from its
+    /// `VIRT_ADDR_SIGN_EXTEND_BIT`: the widest canonical low-half address has every
+    /// bit above that boundary clear, one past it is non-canonical, and canonicalizing
+    /// that non-canonical value produces the matching minimal high-half address
+    /// (every bit at or above the boundary set, everything below clear).
+    fn assert_sign_extends_from_its_boundary_bit<A: AddressArchitecture>() {
+        let bit = A::VIRT_ADDR_SIGN_EXTEND_BIT;
+        let max_low = (1usize << bit) - 1;
+        let min_high = !max_low;
+
+        assert!(is_canonical_virtual_address::<A>(max_low));
+        assert!(!is_canonical_virtual_address::<A>(max_low + 1));
+        assert!(is_canonical_virtual_address::<A>(min_high));
+        assert_eq!(canonicalize_virtual_address::<A>(max_low + 1), min_high);
+    }
+
+    /// Asserts that `A`'s physical addresses are masked to `bits` bits: the widest
+    /// canonical address has every bit at or above `bits` clear, one past it is
+    /// non-canonical, and canonicalizing that value masks the excess bits away.
+    fn assert_masks_to_n_bit_physical_address_space<A: AddressArchitecture>(bits: usize) {
+        let max_canonical = (1usize << bits) - 1;
+
+        assert!(is_canonical_physical_address::<A>(max_canonical));
+        assert!(!is_canonical_physical_address::<A>(max_canonical + 1));
+        assert_eq!(canonicalize_physical_address::<A>(max_canonical + 1), 0);
+    }
+
+    /// Asserts that `A`'s virtual addresses must be *zero*-extended above its
+    /// `VIRT_ADDR_SIGN_EXTEND_BIT` (an aarch64 TTBR0 lower-half region), unlike the
+    /// sign-extended upper-half regions `assert_sign_extends_from_its_boundary_bit` covers.
+    fn assert_zero_extends_above_its_boundary_bit<A: AddressArchitecture>() {
+        let bit = A::VIRT_ADDR_SIGN_EXTEND_BIT;
+        let max_low = (1usize << (bit + 1)) - 1;
+
+        assert!(is_canonical_virtual_address::<A>(0));
+        assert!(is_canonical_virtual_address::<A>(max_low));
+        assert!(!is_canonical_virtual_address::<A>(max_low + 1));
+        assert!(!is_canonical_virtual_address::<A>(usize::MAX));
+        // A value with a bit clearly above the boundary set is masked back down to zero.
+        assert_eq!(canonicalize_virtual_address::<A>(1usize << (bit + 1)), 0);
+    }
+
+    #[test]
+    fn x86_64_sign_extends_from_bit_47() {
+        assert_sign_extends_from_its_boundary_bit::<X86_64>();
+    }
+
+    #[test]
+    fn x86_64_masks_to_52_bit_physical_address_space() {
+        assert_masks_to_n_bit_physical_address_space::<X86_64>(52);
+    }
+
+    #[test]
+    fn aarch64_sign_extends_from_bit_47_regardless_of_granule() {
+        assert_sign_extends_from_its_boundary_bit::<Aarch64Granule4KiB>();
+        assert_sign_extends_from_its_boundary_bit::<Aarch64Granule64KiB>();
+    }
+
+    #[test]
+    fn aarch64_masks_to_48_bit_physical_address_space_regardless_of_granule() {
+        assert_masks_to_n_bit_physical_address_space::<Aarch64Granule4KiB>(48);
+        assert_masks_to_n_bit_physical_address_space::<Aarch64Granule64KiB>(48);
+    }
+
+    #[test]
+    fn aarch64_ttbr0_zero_extends_from_bit_47_regardless_of_granule() {
+        assert_zero_extends_above_its_boundary_bit::<Aarch64TTBR0Granule4KiB>();
+        assert_zero_extends_above_its_boundary_bit::<Aarch64TTBR0Granule64KiB>();
+    }
+
+    #[test]
+    fn aarch64_ttbr0_masks_to_48_bit_physical_address_space_regardless_of_granule() {
+        assert_masks_to_n_bit_physical_address_space::<Aarch64TTBR0Granule4KiB>(48);
+        assert_masks_to_n_bit_physical_address_space::<Aarch64TTBR0Granule64KiB>(48);
+    }
+
+    #[test]
+    fn riscv_sv39_sign_extends_from_bit_38() {
+        assert_sign_extends_from_its_boundary_bit::<RiscvSv39>();
+    }
+
+    #[test]
+    fn riscv_sv48_sign_extends_from_bit_47() {
+        assert_sign_extends_from_its_boundary_bit::<RiscvSv48>();
+    }
+
+    #[test]
+    fn riscv_sv57_sign_extends_from_bit_56() {
+        assert_sign_extends_from_its_boundary_bit::<RiscvSv57>();
+    }
+
+    #[test]
+    fn riscv_sv39_sv48_sv57_mask_to_56_bit_physical_address_space() {
+        assert_masks_to_n_bit_physical_address_space::<RiscvSv39>(56);
+        assert_masks_to_n_bit_physical_address_space::<RiscvSv48>(56);
+        assert_masks_to_n_bit_physical_address_space::<RiscvSv57>(56);
+    }
+}
+
+mod page_table_entry_translate {
+    use crate::addr::*;
+    use crate::page_table_entry::*;
+
+    // Fake "physical" addresses of the page tables used below; `access` maps each
+    // one back to the in-memory array standing in for that table.
+    const P4_ADDR: usize = 0x1000;
+    const P3_ADDR: usize = 0x2000;
+    const P2_ADDR: usize = 0x3000;
+    const P1_ADDR: usize = 0x4000;
+
+    fn empty_table() -> [PageTableEntry; 512] {
+        [PageTableEntry::from_address(PhysicalAddress::zero(), EntryFlags::empty()); 512]
+    }
+
+    #[test]
+    fn huge_page_at_p3_combines_1gib_frame_with_low_offset_bits() {
+        let mut p4 = empty_table();
+        let mut p3 = empty_table();
+        p4[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P3_ADDR), EntryFlags::PRESENT);
+        p3[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(0x4000_0000), EntryFlags::PRESENT | EntryFlags::HUGE_PAGE);
+
+        let access = |addr: PhysicalAddress| -> *const PageTableEntry {
+            match addr.value() {
+                P4_ADDR => p4.as_ptr(),
+                P3_ADDR => p3.as_ptr(),
+                other => panic!("unexpected table address {:#x}", other),
+            }
+        };
+
+        let root = PhysicalAddress::new_canonical(P4_ADDR);
+        let virt = VirtualAddress::new_canonical(0x1234);
+        assert_eq!(translate(root, virt, access), Some(PhysicalAddress::new_canonical(0x4000_1234)));
+    }
+
+    #[test]
+    fn huge_page_at_p2_combines_2mib_frame_with_low_offset_bits() {
+        let mut p4 = empty_table();
+        let mut p3 = empty_table();
+        let mut p2 = empty_table();
+        p4[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P3_ADDR), EntryFlags::PRESENT);
+        p3[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P2_ADDR), EntryFlags::PRESENT);
+        p2[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(0x0020_0000), EntryFlags::PRESENT | EntryFlags::HUGE_PAGE);
+
+        let access = |addr: PhysicalAddress| -> *const PageTableEntry {
+            match addr.value() {
+                P4_ADDR => p4.as_ptr(),
+                P3_ADDR => p3.as_ptr(),
+                P2_ADDR => p2.as_ptr(),
+                other => panic!("unexpected table address {:#x}", other),
+            }
+        };
 
-}
\ No newline at end of file
+        let root = PhysicalAddress::new_canonical(P4_ADDR);
+        let virt = VirtualAddress::new_canonical(0x5678);
+        assert_eq!(translate(root, virt, access), Some(PhysicalAddress::new_canonical(0x0020_5678)));
+    }
+
+    #[test]
+    fn walks_all_four_levels_for_a_standard_4kib_leaf() {
+        let mut p4 = empty_table();
+        let mut p3 = empty_table();
+        let mut p2 = empty_table();
+        let mut p1 = empty_table();
+        p4[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P3_ADDR), EntryFlags::PRESENT);
+        p3[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P2_ADDR), EntryFlags::PRESENT);
+        p2[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(P1_ADDR), EntryFlags::PRESENT);
+        p1[0] = PageTableEntry::from_address(PhysicalAddress::new_canonical(0x9000), EntryFlags::PRESENT);
+
+        let access = |addr: PhysicalAddress| -> *const PageTableEntry {
+            match addr.value() {
+                P4_ADDR => p4.as_ptr(),
+                P3_ADDR => p3.as_ptr(),
+                P2_ADDR => p2.as_ptr(),
+                P1_ADDR => p1.as_ptr(),
+                other => panic!("unexpected table address {:#x}", other),
+            }
+        };
+
+        let root = PhysicalAddress::new_canonical(P4_ADDR);
+        let virt = VirtualAddress::new_canonical(0xAB);
+        assert_eq!(translate(root, virt, access), Some(PhysicalAddress::new_canonical(0x9000 | 0xAB)));
+    }
+
+    #[test]
+    fn stops_early_and_returns_none_on_a_non_present_entry() {
+        let p4 = empty_table(); // entry 0 is all zero, i.e. not PRESENT
+
+        let access = |addr: PhysicalAddress| -> *const PageTableEntry {
+            match addr.value() {
+                P4_ADDR => p4.as_ptr(),
+                other => panic!("unexpected table address {:#x}", other),
+            }
+        };
+
+        let root = PhysicalAddress::new_canonical(P4_ADDR);
+        let virt = VirtualAddress::new_canonical(0x1234);
+        assert_eq!(translate(root, virt, access), None);
+    }
+}
+
+mod trusted_list {
+    use core::marker::PhantomData;
+    use crate::{trusted_list::*, unit::*, range::*};
+
+    fn frame(number: usize) -> Frame {
+        Frame { number, _size: PhantomData }
+    }
+
+    #[test]
+    fn overlaps_does_not_count_merely_touching_intervals() {
+        let mut set = IntervalSet::new();
+        set.insert(0, 9);
+
+        // (0, 9) and (10, 19) touch but don't share a frame.
+        assert!(!set.overlaps(10, 19));
+        assert!(set.overlaps(9, 19));
+        assert!(set.overlaps(5, 15));
+    }
+
+    #[test]
+    fn overlaps_skips_the_gap_between_two_stored_intervals() {
+        let mut set = IntervalSet::new();
+        set.insert(0, 9);
+        set.insert(20, 29);
+
+        // (10, 19) falls entirely in the gap between the two stored intervals.
+        assert!(!set.overlaps(10, 19));
+        assert!(set.overlaps(15, 25));
+        assert!(set.overlaps(29, 30));
+    }
+
+    #[test]
+    fn insert_coalesces_overlapping_and_adjacent_intervals() {
+        let mut set = IntervalSet::new();
+        set.insert(0, 9);
+        set.insert(10, 19);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.get(0), (0, 19));
+
+        set.insert(30, 39);
+        assert_eq!(set.len(), 2);
+
+        // Bridges the gap between the two existing entries into one.
+        set.insert(20, 29);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.get(0), (0, 39));
+    }
+
+    #[test]
+    fn contains_range_requires_full_containment() {
+        let mut set = IntervalSet::new();
+        set.insert(5, 15);
+
+        assert!(set.contains_range(5, 15));
+        assert!(set.contains_range(7, 10));
+        assert!(!set.contains_range(0, 5));
+        assert!(!set.contains_range(15, 20));
+    }
+
+    #[test]
+    fn push_unique_accepts_adjacent_but_rejects_overlapping_ranges() {
+        let mut list = TrustedList::new();
+        assert!(list.push_unique(FrameRange::new(frame(0), frame(9))).is_none());
+
+        // Adjacent to the existing entry: must be accepted, not rejected as "overlapping".
+        assert!(list.push_unique(FrameRange::new(frame(10), frame(19))).is_none());
+        assert_eq!(list.len(), 1);
+
+        // Actually overlaps the merged (0, 19) entry: must be rejected.
+        let rejected = FrameRange::new(frame(15), frame(25));
+        assert_eq!(list.push_unique(rejected.clone()), Some(rejected));
+    }
+}
@@ -0,0 +1,56 @@
+use crate::addr::{PhysicalAddress, VirtualAddress, PAGE_SIZE};
+
+/// A way to translate between [`VirtualAddress`]es and [`PhysicalAddress`]es,
+/// e.g. via a fixed-offset direct map or a full page-table walk.
+pub trait Translation {
+    /// Translates a virtual address to its mapped physical address, or `None`
+    /// if this translation doesn't cover `virt`.
+    fn virt_to_phys(&self, virt: VirtualAddress) -> Option<PhysicalAddress>;
+    /// Translates a physical address to its mapped virtual address, or `None`
+    /// if this translation doesn't cover `phys`.
+    fn phys_to_virt(&self, phys: PhysicalAddress) -> Option<VirtualAddress>;
+}
+
+/// A [`Translation`] that maps every virtual address `va` to `va + offset`
+/// and back via `va - offset`, e.g. the fixed-offset direct map a kernel sets
+/// up over all of physical memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearTranslation {
+    offset: isize,
+}
+
+impl LinearTranslation {
+    /// Creates a new `LinearTranslation` with the given `offset`, or returns
+    /// `None` if `offset` isn't a multiple of [`PAGE_SIZE`].
+    pub fn new(offset: isize) -> Option<LinearTranslation> {
+        if offset % (PAGE_SIZE as isize) != 0 {
+            return None;
+        }
+        Some(LinearTranslation { offset })
+    }
+
+    /// Returns this translation's fixed virtual-to-physical offset.
+    pub const fn offset(&self) -> isize {
+        self.offset
+    }
+}
+
+impl Translation for LinearTranslation {
+    fn virt_to_phys(&self, virt: VirtualAddress) -> Option<PhysicalAddress> {
+        let translated = if self.offset >= 0 {
+            virt.value().checked_add(self.offset as usize)?
+        } else {
+            virt.value().checked_sub(self.offset.unsigned_abs())?
+        };
+        Some(PhysicalAddress::new_canonical(translated))
+    }
+
+    fn phys_to_virt(&self, phys: PhysicalAddress) -> Option<VirtualAddress> {
+        let translated = if self.offset >= 0 {
+            phys.value().checked_sub(self.offset as usize)?
+        } else {
+            phys.value().checked_add(self.offset.unsigned_abs())?
+        };
+        Some(VirtualAddress::new_canonical(translated))
+    }
+}
@@ -9,26 +9,85 @@ use crate::*;
 
 verus! {
 
+/// A set of accepted [`FrameRange`]s that are pairwise non-overlapping.
 pub struct ChunkList(pub(crate) Vec<FrameRange>);
 
+/// An exclusive, verified handle to a [`FrameRange`] that has been checked, at
+/// construction time, to not overlap any other chunk already accepted into the
+/// same [`ChunkList`].
 pub struct TrustedPChunk {
     frames: FrameRange
 }
 
+/// Ghost-level equivalent of [`FrameRange::overlaps`](crate::FrameRange::overlaps),
+/// stated over the raw inclusive bounds so it's usable from spec positions, which
+/// cannot call the `exec fn overlaps` itself.
+pub open spec fn frame_ranges_overlap(a: FrameRange, b: FrameRange) -> bool {
+    a.0.start <= a.0.end && b.0.start <= b.0.end
+        && a.0.start <= b.0.end && b.0.start <= a.0.end
+}
+
+/// Ghost-level equivalent of [`TrustedPChunk::object_overlaps_in_list`], used as the
+/// ground truth in `ensures` clauses since those can't call that `exec fn` either.
+pub open spec fn chunk_overlaps_list(list: Seq<FrameRange>, elem: FrameRange, i: int) -> bool
+    decreases list.len() - i when 0 <= i <= list.len()
+{
+    if i >= list.len() {
+        false
+    } else {
+        frame_ranges_overlap(list[i], elem) || chunk_overlaps_list(list, elem, i + 1)
+    }
+}
+
 impl TrustedPChunk {
-    fn new(frames: FrameRange, chunk_list: &mut ChunkList) -> Option<Self> {
+    /// Attempts to accept `frames` as a new chunk in `chunk_list`.
+    ///
+    /// Returns `None` if `frames` is empty (`start > end`) or overlaps a chunk
+    /// already in `chunk_list`, leaving `chunk_list` unchanged. On `Some(chunk)`,
+    /// `frames` overlapped none of `chunk_list`'s prior entries and has been
+    /// appended to it.
+    fn new(frames: FrameRange, chunk_list: &mut ChunkList) -> (result: Option<Self>)
+        ensures
+            result.is_Some() ==> !chunk_overlaps_list(old(chunk_list).0@, frames, 0),
+    {
         if frames.0.start > frames.0.end {
             None
         } else if Self::range_overlaps_in_list(chunk_list, frames.clone()) {
             None
         } else {
+            chunk_list.0.push(frames.clone());
             Some( TrustedPChunk{frames} )
         }
     }
 
-    fn range_overlaps_in_list(list: &mut ChunkList, elem: FrameRange) -> bool {
-        true
+    /// Returns `true` if `elem` overlaps any entry already in `list`, i.e. is
+    /// equivalent to `exists|i| list.0[i].overlaps(elem)`, proven by delegating to
+    /// the decreasing recursive helper [`Self::object_overlaps_in_list`].
+    fn range_overlaps_in_list(list: &mut ChunkList, elem: FrameRange) -> (result: bool)
+        ensures
+            result == chunk_overlaps_list(list.0@, elem, 0),
+    {
+        Self::object_overlaps_in_list(&list.0, &elem, 0)
+    }
+
+    /// Checks whether `elem` overlaps `list[i]` or any entry after it, recursing
+    /// one index at a time so the check is provably total over the remaining
+    /// `list.len() - i` entries.
+    fn object_overlaps_in_list(list: &Vec<FrameRange>, elem: &FrameRange, i: usize) -> (result: bool)
+        requires
+            i <= list.len(),
+        ensures
+            result == chunk_overlaps_list(list@, *elem, i as int),
+        decreases list.len() - i,
+    {
+        if i >= list.len() {
+            false
+        } else if list.index(i).overlaps(elem) {
+            true
+        } else {
+            Self::object_overlaps_in_list(list, elem, i + 1)
+        }
     }
 }
 
-}
\ No newline at end of file
+}
@@ -6,104 +6,236 @@ use option::{*, Option::*};
 use vec::Vec;
 
 use crate::*;
+use crate::range::FrameRange;
 
 verus!
 {
 
-pub struct TrustedList{
-    list: Vec<FrameRange>
+/// A sorted set of disjoint, pairwise-non-adjacent inclusive `(start, end)` intervals.
+///
+/// Kept sorted by `start` with the invariant that no two entries overlap *or* touch
+/// (entry `i`'s `end + 1 < entry i+1's start`), so any two intervals that overlap or
+/// abut each other are always coalesced into a single wider entry. This turns overlap
+/// queries and insertions into a binary search over the stored entries plus a single
+/// forward walk to find the (small) run that needs merging, rather than an O(n) scan
+/// of every entry.
+///
+/// Backed by `vec::Vec` (vstd's verified vector) rather than `SmallVec`, since external
+/// container types aren't verifiable inside a `verus!` block the way vstd's own `Vec` is.
+pub struct IntervalSet {
+    intervals: Vec<(usize, usize)>,
 }
 
-impl TrustedList {
-    pub fn is_empty(&self) -> bool {
-        self.list.len() == 0
+impl IntervalSet {
+    pub fn new() -> (result: Self)
+        ensures
+            result.intervals.len() == 0,
+    {
+        IntervalSet { intervals: Vec::new() }
     }
 
     pub fn len(&self) -> usize {
-        self.list.len()
+        self.intervals.len()
     }
 
-    fn push(&mut self, elem: FrameRange) {
-        self.list.push(elem);
+    pub fn is_empty(&self) -> bool {
+        self.intervals.len() == 0
     }
 
-    fn pop(&mut self) -> FrameRange 
-        requires 
-            old(self).list.len() > 0
+    /// Returns the `(start, end)` of the `i`-th interval, in sorted order.
+    pub fn get(&self, i: usize) -> (result: (usize, usize))
+        requires
+            i < self.intervals.len(),
     {
-        self.list.pop()
+        self.intervals.index(i).clone()
     }
 
-    pub fn push_unique(&mut self, elem: FrameRange) -> Option<FrameRange> {
-        if true { //self.object_overlaps_in_list(&elem) {
-            Some(elem)
+    /// Binary-searches for the index of the first interval whose `end + 1 >= start`,
+    /// i.e. the first interval that could possibly overlap or abut `[start, ..]`.
+    /// Returns `self.len()` if every interval lies entirely below `start`.
+    fn lower_bound(&self, start: usize) -> (result: usize)
+        ensures
+            result <= self.intervals.len(),
+    {
+        let mut lo: usize = 0;
+        let mut hi: usize = self.intervals.len();
+        while lo < hi
+            invariant
+                lo <= hi <= self.intervals.len(),
+            decreases hi - lo,
+        {
+            let mid = lo + (hi - lo) / 2;
+            let (_mstart, mend) = *self.intervals.index(mid);
+            if mend + 1 < start {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Returns `true` if `[start, end]` overlaps any stored interval.
+    ///
+    /// `lower_bound` only guarantees `iend + 1 >= start`, which also matches an
+    /// interval that merely *touches* `[start, end]` from below (e.g. a stored
+    /// `(0, 9)` against a query of `(10, ..)`). Such an interval shares no frame
+    /// with the query, so the search moves on to the interval after it, which is
+    /// then the first one with `iend >= start`. Checking just that one candidate is
+    /// enough: since stored intervals are sorted and neither overlap nor touch
+    /// each other, every interval after it has a strictly larger `start`, so if
+    /// its `start` lies past `end`, none of the later ones can overlap either.
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        let mut idx = self.lower_bound(start);
+        if idx < self.intervals.len() && self.intervals.index(idx).1 < start {
+            idx = idx + 1;
+        }
+        if idx < self.intervals.len() {
+            let (istart, _iend) = *self.intervals.index(idx);
+            istart <= end
         } else {
-            self.push(elem);
-            None
+            false
         }
+    }
 
+    /// Returns `true` if some single stored interval fully contains `[start, end]`.
+    pub fn contains_range(&self, start: usize, end: usize) -> bool {
+        let idx = self.lower_bound(start);
+        if idx < self.intervals.len() {
+            let (istart, iend) = *self.intervals.index(idx);
+            istart <= start && end <= iend
+        } else {
+            false
+        }
     }
 
-    fn object_in_list_rec(&self, start_index: usize, obj: &FrameRange) -> (result: Option<usize>) 
-        requires 
-            self.list.len() > 0,
-            start_index < self.list.len(),
-            start_index >= 0
-        ensures
-            result.is_Some() ==> (self.list[result.get_Some_0() as int].0.start == obj.0.start) && (self.list[result.get_Some_0() as int].0.end == obj.0.end),
-            // result.is_None() ==> forall|i: int| 
-            //     #![trigger obj.0, self.list@.index(i)]
-            //     0 <= i <= start_index ==> (self.list@.index(i).0.start != obj.0.start) || (self.list@.index(i).0.end != obj.0.end),
-        decreases start_index
+    /// Inserts `[start, end]`, merging it with every interval it overlaps or touches
+    /// into a single widened entry.
+    pub fn insert(&mut self, start: usize, end: usize)
+        requires
+            start <= end,
     {
-        // let list_obj = self.list@.index(start_index);
-        if (obj.0.start == self.list.index(start_index).0.start) && (obj.0.end == self.list.index(start_index).0.end) {
-            return Some(start_index);
+        let first = self.lower_bound(start);
+
+        let mut merged_start = start;
+        let mut merged_end = end;
+        let mut last = first;
+        while last < self.intervals.len()
+            invariant
+                first <= last <= self.intervals.len(),
+            decreases self.intervals.len() - last,
+        {
+            let (istart, iend) = *self.intervals.index(last);
+            if istart > merged_end + 1 {
+                break;
+            }
+            if istart < merged_start {
+                merged_start = istart;
+            }
+            if iend > merged_end {
+                merged_end = iend;
+            }
+            last = last + 1;
+        }
+
+        if first == last {
+            // Nothing to merge with: make room at `first` and insert, as in a sorted
+            // insertion into a plain list.
+            self.intervals.push((merged_start, merged_end));
+            let mut i = self.intervals.len() - 1;
+            while i > first
+                invariant
+                    first < i <= self.intervals.len(),
+                decreases i,
+            {
+                let prev = self.intervals.index(i - 1).clone();
+                self.intervals.set(i, prev);
+                i = i - 1;
+            }
+            self.intervals.set(first, (merged_start, merged_end));
+        } else {
+            // Overwrite the first consumed slot with the merged interval, then shift
+            // everything after the consumed run left to close the gap it left behind.
+            self.intervals.set(first, (merged_start, merged_end));
+            let mut src = last;
+            let mut dst = first + 1;
+            while src < self.intervals.len()
+                invariant
+                    dst <= src,
+                decreases self.intervals.len() - src,
+            {
+                let v = self.intervals.index(src).clone();
+                self.intervals.set(dst, v);
+                src = src + 1;
+                dst = dst + 1;
+            }
+            let removed = last - first;
+            let mut to_pop = removed - 1;
+            while to_pop > 0
+                decreases to_pop,
+            {
+                self.intervals.pop();
+                to_pop = to_pop - 1;
+            }
         }
+    }
 
-        if start_index == 0 {
-            return None;
+    /// Inserts `[start, end]`, returning `true` iff it added coverage the set didn't
+    /// already have, i.e. `false` if `[start, end]` was already fully contained.
+    pub fn insert_range(&mut self, start: usize, end: usize) -> (result: bool)
+        requires
+            start <= end,
+    {
+        if self.contains_range(start, end) {
+            return false;
         }
+        self.insert(start, end);
+        true
+    }
+}
 
-        return self.object_in_list_rec(start_index - 1, obj);
+/// A set of non-overlapping [`FrameRange`]s, backed by an [`IntervalSet`] of
+/// `(start_frame_number, end_frame_number)` pairs so overlap checks and the search
+/// for an insertion's merge run only compare the handful of entries near the
+/// binary-searched insertion point, instead of scanning every entry in the set.
+/// An insertion still shifts every entry stored after that point.
+pub struct TrustedList {
+    intervals: IntervalSet,
+}
+
+impl TrustedList {
+    pub fn new() -> Self {
+        TrustedList { intervals: IntervalSet::new() }
     }
 
-    fn object_in_list(&self, obj: &FrameRange) -> (result: Option<usize>) 
-        ensures
-            result.is_Some() ==> (self.list[result.get_Some_0() as int].0.start == obj.0.start) && (self.list[result.get_Some_0() as int].0.end == obj.0.end),
-            // result.is_None() ==> forall|i: int| 
-            //     0 <= i < self.list.len() ==> (self.list[i].0.start != obj.0.start) || (self.list[i].0.end != obj.0.end),
-    {
-        let mut i = 0;
-        while i < self.list.len() {
-            if (self.list.index(i).0.start == obj.0.start) && (self.list.index(i).0.end == obj.0.end) {
-                return Some(i);
-            }
-            i = i+1;
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Inserts `elem` into the set, unless it overlaps an entry already present,
+    /// in which case `elem` is handed back and the set is left unchanged.
+    pub fn push_unique(&mut self, elem: FrameRange) -> Option<FrameRange> {
+        let start = elem.start().number();
+        let end = elem.end().number();
+        if start > end {
+            return Some(elem);
+        }
+        if self.intervals.overlaps(start, end) {
+            return Some(elem);
         }
+        self.intervals.insert(start, end);
         None
     }
-    // proof fn object_overlaps_in_list(&self, start_index: int, obj: &FrameRange) -> (result: Option<int>) 
-    //     requires 
-    //         self.list.len() > 0,
-    //         start_index <= self.list.len(),
-    //         start_index >= 0
-    //     ensures
-    //         result.is_Some() ==> self.list[result.get_Some_0()].0.start == obj.0.start
-    //     decreases start_index
-    // {
-    //     let list_obj = self.list@.index(start_index);
-    //     if (obj.0.start == list_obj.0.start) && (obj.0.end == list_obj.0.end) {
-    //         return Some(start_index);
-    //     }
-
-    //     if start_index == 0 {
-    //         return None;
-    //     }
-
-    //     return self.object_in_list(start_index - 1, obj);
-    // }
-}
 
+    /// Returns `true` if `obj` is fully contained in some single entry already in
+    /// the set; `obj` need not match that entry's bounds exactly.
+    fn object_in_list(&self, obj: &FrameRange) -> bool {
+        self.intervals.contains_range(obj.start().number(), obj.end().number())
+    }
+}
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,73 @@
+#![allow(unused_imports)]
+use builtin::*;
+use builtin_macros::*;
+use crate::pervasive::*;
+use option::{*, Option::*};
+
+use crate::range_inclusive::RangeInclusiveIterator;
+
+verus! {
+
+/// Pure model of what [`RangeInclusiveIterator::next`](crate::range_inclusive::RangeInclusiveIterator)
+/// yields over its remaining `n` calls, starting from `offset`: the elements
+/// `offset, offset + 1, ..., offset + n - 1`, stopping early if `offset` runs past `end`.
+pub open spec fn iterate_model(offset: int, end: int, n: nat) -> Seq<int>
+    decreases n
+{
+    if n == 0 || offset > end {
+        Seq::empty()
+    } else {
+        seq![offset] + iterate_model(offset + 1, end, (n - 1) as nat)
+    }
+}
+
+/// Verified re-implementation of [`RangeInclusiveIterator::next`](crate::range_inclusive::RangeInclusiveIterator),
+/// specialized to `Idx = usize` (the only concrete case Verus can reason about, since
+/// [`core::iter::Step`] is an unconstrained trait bound). For `usize`,
+/// `Step::forward_checked(offset, 1)` is exactly `offset.checked_add(1)`, so this is
+/// the real `next()`'s logic rather than an unrelated stand-in.
+pub fn verified_next(it: &mut RangeInclusiveIterator<usize>) -> (result: Option<usize>)
+    requires
+        old(it).offset <= old(it).end + 1,
+        old(it).end < usize::MAX,
+    ensures
+        it.end == old(it).end,
+        it.offset <= it.end + 1,
+        result.is_Some() ==> old(it).offset <= old(it).end,
+        result.is_Some() ==> result.get_Some_0() == old(it).offset,
+        result.is_Some() ==> it.offset == old(it).offset + 1,
+        result.is_None() ==> old(it).offset == old(it).end + 1,
+        result.is_None() ==> it.offset == old(it).offset,
+{
+    if it.offset > it.end {
+        None
+    } else {
+        let n = it.offset;
+        it.offset = it.offset + 1;
+        Some(n)
+    }
+}
+
+/// Proves that [`iterate_model`] over a non-empty `[start, end]`, run for exactly
+/// `n = end - start + 1` steps, yields `n` elements whose `i`-th is `start + i`:
+/// every element of the range once each, in increasing order with no duplicates
+/// and no skipped values. The lemma speaks only of the model; it does not mention
+/// [`verified_next`], whose bound `offset <= end + 1` is stated in its own `ensures`.
+pub proof fn yields_exactly_its_elements(start: int, end: int, n: nat)
+    requires
+        start <= end,
+        n == (end - start + 1),
+    ensures
+        iterate_model(start, end, n).len() == n,
+        forall|i: int| 0 <= i < n ==> #[trigger] iterate_model(start, end, n)[i] == start + i,
+        forall|i: int, j: int|
+            0 <= i < n && 0 <= j < n && i != j
+                ==> iterate_model(start, end, n)[i] != iterate_model(start, end, n)[j],
+    decreases n
+{
+    if n > 0 {
+        yields_exactly_its_elements(start + 1, end, (n - 1) as nat);
+    }
+}
+
+}
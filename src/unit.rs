@@ -0,0 +1,411 @@
+use core::{
+    fmt,
+    iter::Step,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
+
+use crate::addr::*;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A type that can be the element of a [`crate::range::AddressRange`]:
+/// either a [`Frame`] (backed by a [`PhysicalAddress`]) or a [`Page`] (backed by a [`VirtualAddress`]).
+pub trait Unit: Copy + Clone + PartialEq + Eq + PartialOrd + Ord + Step {
+    /// The address type this unit's start address is expressed in.
+    type Address: Address;
+    /// The granule size of this unit, e.g. [`Size4KiB`] for a standard frame/page.
+    type Size: PageSize;
+
+    /// Returns the start address of this unit.
+    fn start_address(&self) -> Self::Address;
+    /// Returns the unit that contains the given address.
+    fn containing_address(addr: Self::Address) -> Self;
+    /// Returns the number of this unit, i.e., its start address divided by its size.
+    fn number(&self) -> usize;
+}
+
+/// A marker trait for the size of a [`Frame`] or [`Page`], implemented only by
+/// [`Size4KiB`], [`Size2MiB`], and [`Size1GiB`] so an allocator can reason about
+/// mixed granularities (regular pages plus huge pages) within one type system.
+pub trait PageSize: private::Sealed + Copy + Clone + PartialEq + Eq + PartialOrd + Ord {
+    /// The log2 of this size's span in bytes, e.g. `12` for a 4KiB page.
+    const SIZE_LOG2: usize;
+    /// The span of this size, in bytes.
+    const SIZE: usize = 1 << Self::SIZE_LOG2;
+    /// A human-readable name for this size, e.g. `"4KiB"`.
+    const SIZE_NAME: &'static str;
+
+    /// Returns [`Self::SIZE_LOG2`]; a `fn` form usable where a const isn't.
+    fn shift() -> usize {
+        Self::SIZE_LOG2
+    }
+    /// Returns [`Self::SIZE`]; a `fn` form usable where a const isn't.
+    fn bytes() -> usize {
+        Self::SIZE
+    }
+    /// Returns the page table level at which a huge mapping of this size is legal,
+    /// e.g. [`PageTableLevel::P2`] for [`Size2MiB`], or [`PageTableLevel::P1`] for
+    /// the standard, non-huge [`Size4KiB`] granule.
+    fn level() -> PageTableLevel;
+}
+
+/// A standard 4KiB page/frame, the default and smallest granularity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Size4KiB;
+/// A 2MiB huge page/frame, covering 512 `Size4KiB` pages.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Size2MiB;
+/// A 1GiB huge page/frame, covering 512 `Size2MiB` pages.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Size1GiB;
+
+impl private::Sealed for Size4KiB {}
+impl private::Sealed for Size2MiB {}
+impl private::Sealed for Size1GiB {}
+
+impl PageSize for Size4KiB {
+    const SIZE_LOG2: usize = 12;
+    const SIZE_NAME: &'static str = "4KiB";
+    fn level() -> PageTableLevel {
+        PageTableLevel::P1
+    }
+}
+impl PageSize for Size2MiB {
+    const SIZE_LOG2: usize = 21;
+    const SIZE_NAME: &'static str = "2MiB";
+    fn level() -> PageTableLevel {
+        PageTableLevel::P2
+    }
+}
+impl PageSize for Size1GiB {
+    const SIZE_LOG2: usize = 30;
+    const SIZE_NAME: &'static str = "1GiB";
+    fn level() -> PageTableLevel {
+        PageTableLevel::P3
+    }
+}
+
+/// A `Frame` is a chunk of **physical** memory aligned to a `S`-sized boundary,
+/// defaulting to a standard [`Size4KiB`] frame.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Frame<S: PageSize = Size4KiB> {
+    pub(crate) number: usize,
+    pub(crate) _size: PhantomData<S>,
+}
+
+impl<S: PageSize> Frame<S> {
+    /// Returns the [`PhysicalAddress`] at the start of this `Frame`.
+    pub const fn start_address(&self) -> PhysicalAddress {
+        PhysicalAddress::new_canonical(self.number << S::SIZE_LOG2)
+    }
+
+    /// Returns the number of this `Frame`, i.e., its start address divided by `S::SIZE`.
+    #[inline(always)]
+    pub const fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the `Frame` containing the given [`PhysicalAddress`].
+    pub const fn containing_address(addr: PhysicalAddress) -> Frame<S> {
+        Frame {
+            number: addr.value() >> S::SIZE_LOG2,
+            _size: PhantomData,
+        }
+    }
+}
+
+impl<S: PageSize> Unit for Frame<S> {
+    type Address = PhysicalAddress;
+    type Size = S;
+
+    fn start_address(&self) -> PhysicalAddress {
+        Frame::start_address(self)
+    }
+    fn containing_address(addr: PhysicalAddress) -> Frame<S> {
+        Frame::containing_address(addr)
+    }
+    fn number(&self) -> usize {
+        Frame::number(self)
+    }
+}
+
+/// Converts a standard [`Size4KiB`] frame into the `T`-sized huge frame that contains it.
+///
+/// This lets [`crate::range::AddressRange::try_into_huge`] promote a 4KiB frame range
+/// to whichever huge granule it's aligned to, without matching on `T` at runtime.
+pub trait IntoHugeFrame<T: PageSize> {
+    /// Performs the conversion.
+    fn into_huge_frame(self) -> Frame<T>;
+}
+impl IntoHugeFrame<Size2MiB> for Frame<Size4KiB> {
+    fn into_huge_frame(self) -> Frame<Size2MiB> {
+        self.into_2mib_frame()
+    }
+}
+impl IntoHugeFrame<Size1GiB> for Frame<Size4KiB> {
+    fn into_huge_frame(self) -> Frame<Size1GiB> {
+        self.into_1gib_frame()
+    }
+}
+
+impl Frame<Size4KiB> {
+    /// Converts this 4KiB frame into the [`Size2MiB`] huge frame that contains it.
+    pub const fn into_2mib_frame(self) -> Frame<Size2MiB> {
+        Frame {
+            number: self.number >> (Size2MiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2),
+            _size: PhantomData,
+        }
+    }
+
+    /// Converts this 4KiB frame into the [`Size1GiB`] huge frame that contains it.
+    pub const fn into_1gib_frame(self) -> Frame<Size1GiB> {
+        Frame {
+            number: self.number >> (Size1GiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2),
+            _size: PhantomData,
+        }
+    }
+}
+
+impl Frame<Size2MiB> {
+    /// Converts this 2MiB huge frame into the first [`Size4KiB`] frame it contains,
+    /// or returns `None` if that frame number would overflow.
+    pub const fn as_4kib_frame(self) -> Option<Frame<Size4KiB>> {
+        match self.number.checked_mul(1 << (Size2MiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2)) {
+            Some(number) => Some(Frame { number, _size: PhantomData }),
+            None => None,
+        }
+    }
+}
+
+impl Frame<Size1GiB> {
+    /// Converts this 1GiB huge frame into the first [`Size4KiB`] frame it contains,
+    /// or returns `None` if that frame number would overflow.
+    pub const fn as_4kib_frame(self) -> Option<Frame<Size4KiB>> {
+        match self.number.checked_mul(1 << (Size1GiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2)) {
+            Some(number) => Some(Frame { number, _size: PhantomData }),
+            None => None,
+        }
+    }
+}
+
+impl<S: PageSize> fmt::Debug for Frame<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Frame<{}>(p{:#X})", S::SIZE_NAME, self.start_address().value())
+    }
+}
+impl<S: PageSize> Add<usize> for Frame<S> {
+    type Output = Frame<S>;
+    fn add(self, rhs: usize) -> Frame<S> {
+        // cannot exceed max page number (which is also max frame number)
+        Frame {
+            number: core::cmp::min(MAX_PAGE_NUMBER, self.number.saturating_add(rhs)),
+            _size: PhantomData,
+        }
+    }
+}
+impl<S: PageSize> AddAssign<usize> for Frame<S> {
+    fn add_assign(&mut self, rhs: usize) {
+        *self = Frame {
+            number: core::cmp::min(MAX_PAGE_NUMBER, self.number.saturating_add(rhs)),
+            _size: PhantomData,
+        };
+    }
+}
+impl<S: PageSize> Sub<usize> for Frame<S> {
+    type Output = Frame<S>;
+    fn sub(self, rhs: usize) -> Frame<S> {
+        Frame {
+            number: self.number.saturating_sub(rhs),
+            _size: PhantomData,
+        }
+    }
+}
+impl<S: PageSize> SubAssign<usize> for Frame<S> {
+    fn sub_assign(&mut self, rhs: usize) {
+        *self = Frame {
+            number: self.number.saturating_sub(rhs),
+            _size: PhantomData,
+        };
+    }
+}
+/// Implementing `Step` allows `Frame` to be used in an [`Iterator`].
+impl<S: PageSize> Step for Frame<S> {
+    #[inline]
+    fn steps_between(start: &Frame<S>, end: &Frame<S>) -> Option<usize> {
+        Step::steps_between(&start.number, &end.number)
+    }
+    #[inline]
+    fn forward_checked(start: Frame<S>, count: usize) -> Option<Frame<S>> {
+        Step::forward_checked(start.number, count).map(|n| Frame { number: n, _size: PhantomData })
+    }
+    #[inline]
+    fn backward_checked(start: Frame<S>, count: usize) -> Option<Frame<S>> {
+        Step::backward_checked(start.number, count).map(|n| Frame { number: n, _size: PhantomData })
+    }
+}
+
+/// A `Page` is a chunk of **virtual** memory aligned to a `S`-sized boundary,
+/// defaulting to a standard [`Size4KiB`] page.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Page<S: PageSize = Size4KiB> {
+    pub(crate) number: usize,
+    pub(crate) _size: PhantomData<S>,
+}
+
+impl<S: PageSize> Page<S> {
+    /// Returns the [`VirtualAddress`] at the start of this `Page`.
+    pub const fn start_address(&self) -> VirtualAddress {
+        VirtualAddress::new_canonical(self.number << S::SIZE_LOG2)
+    }
+
+    /// Returns the number of this `Page`, i.e., its start address divided by `S::SIZE`.
+    #[inline(always)]
+    pub const fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the `Page` containing the given [`VirtualAddress`].
+    pub const fn containing_address(addr: VirtualAddress) -> Page<S> {
+        Page {
+            number: addr.value() >> S::SIZE_LOG2,
+            _size: PhantomData,
+        }
+    }
+}
+
+impl<S: PageSize> Unit for Page<S> {
+    type Address = VirtualAddress;
+    type Size = S;
+
+    fn start_address(&self) -> VirtualAddress {
+        Page::start_address(self)
+    }
+    fn containing_address(addr: VirtualAddress) -> Page<S> {
+        Page::containing_address(addr)
+    }
+    fn number(&self) -> usize {
+        Page::number(self)
+    }
+}
+
+impl Page<Size4KiB> {
+    /// Converts this 4KiB page into the [`Size2MiB`] huge page that contains it.
+    pub const fn into_2mib_page(self) -> Page<Size2MiB> {
+        Page {
+            number: self.number >> (Size2MiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2),
+            _size: PhantomData,
+        }
+    }
+
+    /// Converts this 4KiB page into the [`Size1GiB`] huge page that contains it.
+    pub const fn into_1gib_page(self) -> Page<Size1GiB> {
+        Page {
+            number: self.number >> (Size1GiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2),
+            _size: PhantomData,
+        }
+    }
+
+    /// Returns the 9-bit part of this `Page`'s [`VirtualAddress`] that is the index into the P4 page table entries list.
+    pub const fn p4_index(&self) -> usize {
+        (self.number >> 27) & 0x1FF
+    }
+
+    /// Returns the 9-bit part of this `Page`'s [`VirtualAddress`] that is the index into the P3 page table entries list.
+    pub const fn p3_index(&self) -> usize {
+        (self.number >> 18) & 0x1FF
+    }
+
+    /// Returns the 9-bit part of this `Page`'s [`VirtualAddress`] that is the index into the P2 page table entries list.
+    pub const fn p2_index(&self) -> usize {
+        (self.number >> 9) & 0x1FF
+    }
+
+    /// Returns the 9-bit part of this `Page`'s [`VirtualAddress`] that is the index into the P1 page table entries list.
+    ///
+    /// Using this returned `usize` value as an index into the P1 entries list will give you the final PTE,
+    /// from which you can extract the mapped frame's address using [`crate::page_table_entry::PageTableEntry::frame()`].
+    pub const fn p1_index(&self) -> usize {
+        (self.number >> 0) & 0x1FF
+    }
+}
+
+impl Page<Size2MiB> {
+    /// Converts this 2MiB huge page into the first [`Size4KiB`] page it contains,
+    /// or returns `None` if that page number would overflow.
+    pub const fn as_4kib_page(self) -> Option<Page<Size4KiB>> {
+        match self.number.checked_mul(1 << (Size2MiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2)) {
+            Some(number) => Some(Page { number, _size: PhantomData }),
+            None => None,
+        }
+    }
+}
+
+impl Page<Size1GiB> {
+    /// Converts this 1GiB huge page into the first [`Size4KiB`] page it contains,
+    /// or returns `None` if that page number would overflow.
+    pub const fn as_4kib_page(self) -> Option<Page<Size4KiB>> {
+        match self.number.checked_mul(1 << (Size1GiB::SIZE_LOG2 - Size4KiB::SIZE_LOG2)) {
+            Some(number) => Some(Page { number, _size: PhantomData }),
+            None => None,
+        }
+    }
+}
+
+impl<S: PageSize> fmt::Debug for Page<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Page<{}>(v{:#X})", S::SIZE_NAME, self.start_address().value())
+    }
+}
+impl<S: PageSize> Add<usize> for Page<S> {
+    type Output = Page<S>;
+    fn add(self, rhs: usize) -> Page<S> {
+        // cannot exceed max page number (which is also max frame number)
+        Page {
+            number: core::cmp::min(MAX_PAGE_NUMBER, self.number.saturating_add(rhs)),
+            _size: PhantomData,
+        }
+    }
+}
+impl<S: PageSize> AddAssign<usize> for Page<S> {
+    fn add_assign(&mut self, rhs: usize) {
+        *self = Page {
+            number: core::cmp::min(MAX_PAGE_NUMBER, self.number.saturating_add(rhs)),
+            _size: PhantomData,
+        };
+    }
+}
+impl<S: PageSize> Sub<usize> for Page<S> {
+    type Output = Page<S>;
+    fn sub(self, rhs: usize) -> Page<S> {
+        Page {
+            number: self.number.saturating_sub(rhs),
+            _size: PhantomData,
+        }
+    }
+}
+impl<S: PageSize> SubAssign<usize> for Page<S> {
+    fn sub_assign(&mut self, rhs: usize) {
+        *self = Page {
+            number: self.number.saturating_sub(rhs),
+            _size: PhantomData,
+        };
+    }
+}
+/// Implementing `Step` allows `Page` to be used in an [`Iterator`].
+impl<S: PageSize> Step for Page<S> {
+    #[inline]
+    fn steps_between(start: &Page<S>, end: &Page<S>) -> Option<usize> {
+        Step::steps_between(&start.number, &end.number)
+    }
+    #[inline]
+    fn forward_checked(start: Page<S>, count: usize) -> Option<Page<S>> {
+        Step::forward_checked(start.number, count).map(|n| Page { number: n, _size: PhantomData })
+    }
+    #[inline]
+    fn backward_checked(start: Page<S>, count: usize) -> Option<Page<S>> {
+        Step::backward_checked(start.number, count).map(|n| Page { number: n, _size: PhantomData })
+    }
+}